@@ -0,0 +1,184 @@
+// ai.rs
+// Computer opponents that pick a column for the current player. They keep
+// the power-ups they collect but never use them.
+use crate::game::{Game, OBSTACLE, PLAYER_O, PLAYER_X};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+const WIN_SCORE: i32 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    fn search_depth(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Medium => 2,
+            Difficulty::Hard => 4,
+        }
+    }
+}
+
+/// Columns that still have room for a piece.
+fn playable_columns(game: &Game) -> Vec<usize> {
+    let cols = game.get_board()[0].len();
+    (0..cols)
+        .filter(|&col| game.landing_row(col).is_some())
+        .collect()
+}
+
+/// Picks a column for the current player, or `None` when the board is full.
+pub fn choose_column(game: &Game, difficulty: Difficulty) -> Option<usize> {
+    let mut columns = playable_columns(game);
+    if columns.is_empty() {
+        return None;
+    }
+
+    let mut rng = rand::thread_rng();
+    if difficulty == Difficulty::Easy {
+        return columns.choose(&mut rng).copied();
+    }
+
+    // Shuffle first so equally good moves don't always pick the same column.
+    columns.shuffle(&mut rng);
+    let me = game.get_current_player();
+    let depth = difficulty.search_depth();
+
+    columns
+        .into_iter()
+        .map(|col| {
+            let score = match play(game, col) {
+                Some(next) => search(&next, depth - 1, me, i32::MIN + 1, i32::MAX),
+                None => i32::MIN + 1,
+            };
+            (col, score)
+        })
+        .max_by_key(|&(_, score)| score)
+        .map(|(col, _)| col)
+}
+
+/// Picks the piece a waiting targeted power-up should destroy, or `None` to
+/// cancel it when there is nothing to aim at.
+pub fn choose_target(game: &Game) -> Option<(usize, usize)> {
+    let me = game.get_current_player();
+    game.targets().into_iter().max_by_key(|&(row, col)| {
+        let mut next = game.clone();
+        match next.choose_target(row, col) {
+            Ok(_) if next.check_winner() == Some(me) => WIN_SCORE,
+            Ok(_) => evaluate(&next, me),
+            Err(_) => i32::MIN,
+        }
+    })
+}
+
+/// The game after dropping into `col`, with any target picked and the turn
+/// passed on.
+fn play(game: &Game, col: usize) -> Option<Game> {
+    let mut next = game.clone();
+    next.drop_piece(col).ok()?;
+    if next.targeting().is_some() && next.check_winner().is_none() {
+        match choose_target(&next) {
+            Some((row, col)) => {
+                next.choose_target(row, col).ok()?;
+            }
+            None => next.cancel_target(),
+        }
+    }
+    if next.check_winner().is_none() && !next.is_full() {
+        next.switch_player();
+    }
+    Some(next)
+}
+
+/// Minimax with alpha-beta pruning, scored from `me`'s point of view.
+fn search(game: &Game, depth: u32, me: char, mut alpha: i32, mut beta: i32) -> i32 {
+    if let Some(winner) = game.check_winner() {
+        // Prefer quicker wins and slower losses.
+        let score = WIN_SCORE + depth as i32;
+        return if winner == me { score } else { -score };
+    }
+    if game.is_full() {
+        return 0;
+    }
+    if depth == 0 {
+        return evaluate(game, me);
+    }
+
+    let maximizing = game.get_current_player() == me;
+    let mut best = if maximizing { i32::MIN + 1 } else { i32::MAX };
+    for col in playable_columns(game) {
+        let Some(next) = play(game, col) else {
+            continue;
+        };
+        let score = search(&next, depth - 1, me, alpha, beta);
+        if maximizing {
+            best = best.max(score);
+            alpha = alpha.max(score);
+        } else {
+            best = best.min(score);
+            beta = beta.min(score);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+/// Scores every line of `win_length` cells that only one player occupies.
+fn evaluate(game: &Game, me: char) -> i32 {
+    let board = game.get_board();
+    let rows = board.len() as isize;
+    let cols = board[0].len() as isize;
+    let length = game.win_length() as isize;
+    let opponent = if me == PLAYER_X { PLAYER_O } else { PLAYER_X };
+    let directions = [(0, 1), (1, 0), (1, 1), (-1, 1)];
+
+    let mut score = 0;
+    for row in 0..rows {
+        for col in 0..cols {
+            for (dr, dc) in directions {
+                let end_row = row + dr * (length - 1);
+                let end_col = col + dc * (length - 1);
+                if end_row < 0 || end_row >= rows || end_col >= cols {
+                    continue;
+                }
+
+                let (mut mine, mut theirs, mut blocked) = (0, 0, false);
+                for step in 0..length {
+                    let cell = board[(row + dr * step) as usize][(col + dc * step) as usize];
+                    if cell == me {
+                        mine += 1;
+                    } else if cell == opponent {
+                        theirs += 1;
+                    } else if cell == OBSTACLE {
+                        blocked = true;
+                    }
+                }
+
+                if blocked {
+                    continue;
+                }
+                if theirs == 0 {
+                    score += mine * mine;
+                } else if mine == 0 {
+                    score -= theirs * theirs;
+                }
+            }
+        }
+    }
+    score
+}
@@ -1,78 +1,105 @@
-use crate::game::{Game, OBSTACLE, PLAYER_O, PLAYER_X};
+mod audio;
+mod camera;
+mod config;
+mod controls;
+mod display;
+mod gamepad;
+mod inventory;
+mod layout;
+mod particles;
+mod pause;
+mod settings;
+mod skin;
+mod storage;
+mod theme;
+
+use crate::ai;
+use crate::game::{player_index, Game, OBSTACLE, PLAYER_O, PLAYER_X};
+use crate::placement;
+use crate::power_ups::{self, Activation, Effect, PowerUp};
+use audio::{PlaySound, SoundEffect};
+use bevy::audio::AddAudioSource;
+use bevy::input::InputSystem;
 use bevy::prelude::*;
 use bevy::render::camera::ScalingMode;
 use bevy::render::mesh::shape::Circle;
 use bevy::sprite::{ColorMaterial, MaterialMesh2dBundle};
-use std::collections::HashSet;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum PowerUpType {
-    Bomb,
-    Skip,
-    Obstacle,
-}
-
-impl PowerUpType {
-    fn from_char(c: char) -> Option<Self> {
-        match c {
-            'B' => Some(Self::Bomb),
-            'S' => Some(Self::Skip),
-            'H' => Some(Self::Obstacle),
-            _ => None,
-        }
-    }
-
-    fn color(&self) -> Color {
-        match self {
-            Self::Bomb => Color::PURPLE,
-            Self::Skip => Color::GREEN,
-            Self::Obstacle => Color::DARK_GRAY,
-        }
-    }
-
-    fn label(&self) -> &'static str {
-        match self {
-            Self::Bomb => "B",
-            Self::Skip => "S",
-            Self::Obstacle => "H",
-        }
-    }
-}
+use bevy::ui::UiSystem;
+use bevy::window::PrimaryWindow;
+use controls::{Action, InputMap};
+use inventory::ItemSelection;
+use layout::BoardLayout;
+use particles::{ParticleBurst, ParticlePool};
+use settings::{Seat, Settings, SettingsDraft};
+use skin::{LoadedSkin, SkinElement};
+use std::collections::{HashMap, HashSet, VecDeque};
+use theme::Theme;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default, States)]
 enum AppState {
     #[default]
     MainMenu,
+    Settings,
+    Controls,
     InGame,
     GameOver,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default, States)]
+enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Explanation shown on the pause overlay, e.g. which controller went missing.
+#[derive(Resource, Default)]
+struct PauseReason(Option<String>);
+
 #[derive(Resource)]
 struct GameStateResource {
     game: Game,
-    previous_rows: usize,
-    previous_cols: usize,
-    power_ups_enabled: bool,
+    /// Snapshots taken before each move, most recent last, for undo.
+    history: Vec<Game>,
+    /// Set when entering `AppState::InGame` should continue `game` instead of
+    /// starting a new one.
+    resume: bool,
 }
 
 impl Default for GameStateResource {
     fn default() -> Self {
-        let game = Game::new(false);
         Self {
-            previous_rows: game.get_board().len(),
-            previous_cols: game.get_board()[0].len(),
-            game,
-            power_ups_enabled: false,
+            game: Game::new(false),
+            history: Vec::new(),
+            resume: false,
         }
     }
 }
 
+const SAVE_FILE: &str = "savegame.ron";
+
+fn turn_indicator(player: char, theme: &Theme) -> (String, Color) {
+    let text = format!("Player {}'s Turn", player_index(player) + 1);
+    (text, theme.player_color(player))
+}
+
 #[derive(Component)]
 struct StartButton;
 
 #[derive(Component)]
 struct MainMenuButton;
 
+#[derive(Component)]
+struct ContinueButton;
+
+#[derive(Component)]
+struct SettingsButton;
+
+#[derive(Component)]
+struct Toast {
+    timer: Timer,
+}
+
 #[derive(Component)]
 struct TurnIndicator;
 
@@ -81,7 +108,7 @@ struct TurnIndicator;
 struct Cell {
     row: usize,
     col: usize,
-    power_up: Option<PowerUpType>,
+    power_up: Option<&'static dyn PowerUp>,
 }
 #[allow(dead_code)]
 #[derive(Component)]
@@ -100,53 +127,245 @@ struct GameUI;
 #[derive(Component)]
 struct GameOverUI;
 
+/// The board thumbnail on the game over screen.
+#[derive(Component)]
+struct FinalBoard;
+
+#[derive(Component)]
+struct FinalBoardCell;
+
+/// Downward acceleration of falling pieces, in units per second squared.
+const GRAVITY: f32 = 6000.0;
+/// Share of its speed a piece keeps when it bounces off its resting place.
+const BOUNCE: f32 = 0.3;
+/// Below this speed a landing piece settles instead of bouncing again.
+const SETTLE_SPEED: f32 = 150.0;
+/// Landing speed that counts as a full-strength impact.
+const FULL_IMPACT_SPEED: f32 = 2500.0;
+/// How much a piece stretches while falling and flattens on impact.
+const MAX_SQUASH: f32 = 0.2;
+/// Seconds a piece takes to recover from a full-strength impact.
+const SQUASH_TIME: f32 = 0.15;
+/// Speed of the steady fall used with reduced motion.
+const STEADY_FALL_SPEED: f32 = 800.0;
+
+/// A piece or obstacle falling into place. Removed once it has stopped
+/// bouncing and recovered its shape.
 #[derive(Component)]
 struct AnimatePiece {
     target_y: f32,
+    /// Vertical speed, negative while falling.
+    velocity: f32,
+    /// Whether it has hit its resting place yet.
+    landed: bool,
+    /// How flattened it is from the last impact, easing back to 0.
+    squash: f32,
+}
+
+impl AnimatePiece {
+    fn to(target_y: f32) -> Self {
+        Self {
+            target_y,
+            velocity: 0.0,
+            landed: false,
+            squash: 0.0,
+        }
+    }
+
+    /// Squash and stretch as a scale: flattened after an impact, otherwise
+    /// stretched along the direction of travel.
+    fn scale(&self) -> Vec3 {
+        let amount = if self.squash > 0.0 {
+            -self.squash
+        } else {
+            (self.velocity.abs() / FULL_IMPACT_SPEED).min(1.0) * MAX_SQUASH
+        };
+        Vec3::new(1.0 - amount * 0.5, 1.0 + amount, 1.0)
+    }
 }
 
 #[derive(Component)]
 struct GameBackground;
 
+/// The board behind the cells, sized to fit them.
 #[derive(Component)]
-struct MainCamera;
+struct BoardFrame;
+
+/// Seconds the board view takes to grow after an expansion.
+const EXPANSION_TIME: f32 = 0.6;
+/// Seconds a new power-up takes to pop in.
+const POP_IN_TIME: f32 = 0.3;
 
+/// Eases an entity's translation and scale from `from` to `to`.
 #[derive(Component)]
-struct Flashing;
+struct Tween {
+    from: Transform,
+    to: Transform,
+    elapsed: f32,
+    duration: f32,
+    ease: fn(f32) -> f32,
+}
+
+impl Tween {
+    fn new(from: Transform, to: Transform, duration: f32, ease: fn(f32) -> f32) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.0,
+            duration,
+            ease,
+        }
+    }
+}
+
+/// Slow at both ends.
+fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
 
+/// Overshoots slightly before settling.
+fn ease_out_back(t: f32) -> f32 {
+    let overshoot = 1.7;
+    let t = t - 1.0;
+    1.0 + (overshoot + 1.0) * t * t * t + overshoot * t * t
+}
+
+/// Fades a sprite or text in from transparent. Spawned hidden so it doesn't
+/// flash before the fade starts.
 #[derive(Component)]
-struct Explosion {
-    timer: Timer,
+struct FadeIn {
+    elapsed: f32,
+    duration: f32,
+    /// The alpha to fade to, read from the entity on the first frame.
+    alpha: Option<f32>,
+}
+
+impl FadeIn {
+    fn insert(commands: &mut Commands, entity: Entity, duration: f32) {
+        commands.entity(entity).insert((
+            FadeIn {
+                elapsed: 0.0,
+                duration,
+                alpha: None,
+            },
+            Visibility::Hidden,
+        ));
+    }
 }
 
+#[derive(Component)]
+struct MainCamera;
+
+type MainCameraQuery<'w, 's> =
+    Query<'w, 's, (&'static mut OrthographicProjection, &'static mut Transform), With<MainCamera>>;
+
 #[derive(Component)]
 struct StaticObstacle {
     row: usize,
     col: usize,
 }
 
-#[derive(Component)]
-struct PowerUpsToggleButton(bool);
-
 #[derive(Component)]
 struct PowerUpSymbol {
     row: usize,
     col: usize,
 }
 
+#[derive(Component)]
+struct ColumnLabel {
+    col: usize,
+    color: Color,
+}
+
+#[derive(Component)]
+struct GhostPiece;
+
+#[derive(Component)]
+struct ColumnSelector;
+
+/// Column currently targeted by the mouse or the keyboard cursor.
+#[derive(Resource, Default)]
+struct ColumnCursor {
+    col: Option<usize>,
+}
+
+impl ColumnCursor {
+    /// Moves the cursor by `step` columns, wrapping around the board edges.
+    /// The first move starts from the middle column.
+    fn step(&mut self, step: isize, cols: usize) {
+        let col = match self.col {
+            None => cols / 2,
+            Some(col) => (col as isize + step).rem_euclid(cols as isize) as usize,
+        };
+        self.col = Some(col);
+    }
+}
+
+struct DropRequest {
+    col: usize,
+}
+
+impl Event for DropRequest {}
+
+/// The piece a waiting targeted power-up should destroy, or `None` to call
+/// it off. Players aim with drop requests; this comes from cancelling and
+/// from computer seats.
+struct TargetRequest {
+    cell: Option<(usize, usize)>,
+}
+
+impl Event for TargetRequest {}
+
+/// A power-up that appeared on the board between turns.
+struct PowerUpSpawned {
+    col: usize,
+    power_up: &'static dyn PowerUp,
+}
+
+impl Event for PowerUpSpawned {}
+
 struct PowerUpActivated {
     row: usize,
     col: usize,
-    power_up: PowerUpType,
+    power_up: &'static dyn PowerUp,
+    effects: Vec<Effect>,
 }
 
 impl Event for PowerUpActivated {}
 
+/// Seconds between power-ups going off in a chain reaction.
+const CHAIN_STEP_TIME: f32 = 0.35;
+
+/// Power-ups set off by the last move that haven't been shown yet. They are
+/// sent as `PowerUpActivated` one at a time, so a chain plays out in order.
+#[derive(Resource, Default)]
+struct ChainReaction {
+    pending: VecDeque<PowerUpActivated>,
+    /// Seconds until the next one may go off.
+    wait: f32,
+}
+
+/// Sent when a falling piece or obstacle first hits its resting place.
+struct PieceLanded {
+    /// How hard it hit, from 0.0 to 1.0.
+    impact: f32,
+    /// Where it came to rest.
+    position: Vec2,
+    obstacle: bool,
+}
+
+impl Event for PieceLanded {}
+
 fn setup(mut commands: Commands) {
     commands.spawn((Camera2dBundle::default(), MainCamera));
 }
 
-fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+) {
     commands
         .spawn((
             NodeBundle {
@@ -158,7 +377,7 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     flex_direction: FlexDirection::Column,
                     ..default()
                 },
-                background_color: Color::rgb(0.1, 0.1, 0.3).into(),
+                background_color: theme.menu_background.into(),
                 ..default()
             },
             MainMenuUI,
@@ -168,9 +387,9 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 text: Text::from_section(
                     "Rusty Connect Four",
                     TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font: theme.bold_font(&asset_server),
                         font_size: 60.0,
-                        color: Color::GOLD,
+                        color: theme.title,
                     },
                 )
                 .with_alignment(TextAlignment::Center),
@@ -187,11 +406,14 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
 
             parent.spawn(TextBundle {
                 text: Text::from_section(
-                    "Use number keys 1-0 to drop pieces into columns.\nFirst to connect four in a row wins!",
+                    format!(
+                        "Click a column, use number keys 1-0, or move with Left/Right and drop with Enter.\nFirst to connect {} in a row wins!",
+                        settings.game.win_length
+                    ),
                     TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Regular.ttf"),
+                        font: theme.regular_font(&asset_server),
                         font_size: 30.0,
-                        color: Color::WHITE,
+                        color: theme.text,
                     },
                 )
                 .with_alignment(TextAlignment::Center),
@@ -210,26 +432,26 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 .spawn((
                     ButtonBundle {
                         style: Style {
-                            width: Val::Px(300.0),
-                            height: Val::Px(50.0),
+                            width: Val::Px(200.0),
+                            height: Val::Px(65.0),
                             margin: UiRect::all(Val::Px(10.0)),
                             justify_content: JustifyContent::Center,
                             align_items: AlignItems::Center,
                             ..default()
                         },
-                        background_color: Color::GRAY.into(),
+                        background_color: theme.button.into(),
                         ..default()
                     },
-                    PowerUpsToggleButton(false),
+                    StartButton,
                 ))
                 .with_children(|button| {
                     button.spawn(TextBundle {
                         text: Text::from_section(
-                            "Power-ups: OFF",
+                            "Start",
                             TextStyle {
-                                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                                font_size: 30.0,
-                                color: Color::WHITE,
+                                font: theme.bold_font(&asset_server),
+                                font_size: 40.0,
+                                color: theme.text,
                             },
                         )
                         .with_alignment(TextAlignment::Center),
@@ -237,30 +459,63 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     });
                 });
 
+            if storage::config_path(SAVE_FILE).exists() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(200.0),
+                                height: Val::Px(50.0),
+                                margin: UiRect::all(Val::Px(10.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: theme.button.into(),
+                            ..default()
+                        },
+                        ContinueButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle {
+                            text: Text::from_section(
+                                "Continue",
+                                TextStyle {
+                                    font: theme.bold_font(&asset_server),
+                                    font_size: 30.0,
+                                    color: theme.text,
+                                },
+                            )
+                            .with_alignment(TextAlignment::Center),
+                            ..default()
+                        });
+                    });
+            }
+
             parent
                 .spawn((
                     ButtonBundle {
                         style: Style {
                             width: Val::Px(200.0),
-                            height: Val::Px(65.0),
+                            height: Val::Px(50.0),
                             margin: UiRect::all(Val::Px(10.0)),
                             justify_content: JustifyContent::Center,
                             align_items: AlignItems::Center,
                             ..default()
                         },
-                        background_color: Color::rgb(0.15, 0.65, 0.15).into(),
+                        background_color: theme.secondary_button.into(),
                         ..default()
                     },
-                    StartButton,
+                    SettingsButton,
                 ))
                 .with_children(|button| {
                     button.spawn(TextBundle {
                         text: Text::from_section(
-                            "Start",
+                            "Settings",
                             TextStyle {
-                                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                                font_size: 40.0,
-                                color: Color::WHITE,
+                                font: theme.bold_font(&asset_server),
+                                font_size: 30.0,
+                                color: theme.text,
                             },
                         )
                         .with_alignment(TextAlignment::Center),
@@ -270,76 +525,111 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         });
 }
 
-fn power_ups_toggle_system(
+fn main_menu_button_system(
     mut interaction_query: Query<
-        (
-            &Interaction,
-            &mut BackgroundColor,
-            &mut PowerUpsToggleButton,
-            &Children,
-        ),
-        (Changed<Interaction>, With<PowerUpsToggleButton>),
+        (Entity, Ref<Interaction>, &mut BackgroundColor),
+        With<StartButton>,
     >,
-    mut text_query: Query<&mut Text>,
+    focus: Res<gamepad::MenuFocus>,
+    mut app_state: ResMut<NextState<AppState>>,
+    theme: Res<Theme>,
 ) {
-    for (interaction, mut background_color, mut toggle_button, children) in &mut interaction_query {
-        match *interaction {
+    for (button, interaction, mut background_color) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(button, interaction) else {
+            continue;
+        };
+        match interaction {
             Interaction::Pressed => {
-                toggle_button.0 = !toggle_button.0;
-                *background_color = if toggle_button.0 {
-                    Color::DARK_GREEN.into()
-                } else {
-                    Color::GRAY.into()
-                };
+                *background_color = theme.button_pressed.into();
+                app_state.set(AppState::InGame);
+            }
+            Interaction::Hovered => {
+                *background_color = theme.button_hover.into();
+            }
+            Interaction::None => {
+                *background_color = theme.button.into();
+            }
+        }
+    }
+}
 
-                for &child in children.iter() {
-                    if let Ok(mut text) = text_query.get_mut(child) {
-                        text.sections[0].value =
-                            format!("Power-ups: {}", if toggle_button.0 { "ON" } else { "OFF" });
+fn continue_button_system(
+    mut interaction_query: Query<
+        (Entity, Ref<Interaction>, &mut BackgroundColor),
+        With<ContinueButton>,
+    >,
+    focus: Res<gamepad::MenuFocus>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut game_state: ResMut<GameStateResource>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+) {
+    for (button, interaction, mut background_color) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(button, interaction) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Pressed => {
+                *background_color = theme.button_pressed.into();
+                match storage::load_ron::<Game>(SAVE_FILE) {
+                    Ok(Some(game)) => {
+                        game_state.game = game;
+                        game_state.history.clear();
+                        game_state.resume = true;
+                        app_state.set(AppState::InGame);
+                    }
+                    Ok(None) => {
+                        spawn_toast(&mut commands, &asset_server, &theme, "No saved game found.")
+                    }
+                    Err(err) => {
+                        eprintln!("Could not load saved game: {}", err);
+                        spawn_toast(
+                            &mut commands,
+                            &asset_server,
+                            &theme,
+                            "The saved game is damaged.",
+                        );
                     }
                 }
             }
             Interaction::Hovered => {
-                *background_color = if toggle_button.0 {
-                    Color::GREEN.into()
-                } else {
-                    Color::DARK_GRAY.into()
-                };
+                *background_color = theme.button_hover.into();
             }
             Interaction::None => {
-                *background_color = if toggle_button.0 {
-                    Color::DARK_GREEN.into()
-                } else {
-                    Color::GRAY.into()
-                };
+                *background_color = theme.button.into();
             }
         }
     }
 }
 
-fn main_menu_button_system(
+fn settings_button_system(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<StartButton>),
+        (Entity, Ref<Interaction>, &mut BackgroundColor),
+        With<SettingsButton>,
     >,
-    toggle_query: Query<&PowerUpsToggleButton>,
+    focus: Res<gamepad::MenuFocus>,
+    settings: Res<Settings>,
+    preferences: Res<config::Preferences>,
+    mut draft: ResMut<SettingsDraft>,
     mut app_state: ResMut<NextState<AppState>>,
-    mut game_state: ResMut<GameStateResource>,
+    theme: Res<Theme>,
 ) {
-    for (interaction, mut background_color) in &mut interaction_query {
-        match *interaction {
+    for (button, interaction, mut background_color) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(button, interaction) else {
+            continue;
+        };
+        match interaction {
             Interaction::Pressed => {
-                *background_color = Color::rgb(0.10, 0.55, 0.10).into();
-                if let Ok(toggle_button) = toggle_query.get_single() {
-                    game_state.power_ups_enabled = toggle_button.0;
-                }
-                app_state.set(AppState::InGame);
+                *background_color = theme.secondary_button_hover.into();
+                *draft = SettingsDraft::new(&settings, &preferences);
+                app_state.set(AppState::Settings);
             }
             Interaction::Hovered => {
-                *background_color = Color::rgb(0.25, 0.75, 0.25).into();
+                *background_color = theme.secondary_button_hover.into();
             }
             Interaction::None => {
-                *background_color = Color::rgb(0.15, 0.65, 0.15).into();
+                *background_color = theme.secondary_button.into();
             }
         }
     }
@@ -354,23 +644,37 @@ fn cleanup_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenuU
 fn setup_game(
     mut commands: Commands,
     mut state: ResMut<GameStateResource>,
+    mut layout: ResMut<BoardLayout>,
     asset_server: Res<AssetServer>,
-    mut camera_query: Query<(&mut OrthographicProjection, &mut Transform), With<MainCamera>>,
+    mut camera_query: MainCameraQuery,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cursor: ResMut<ColumnCursor>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
 ) {
-    state.game = Game::new(state.power_ups_enabled);
-    state.previous_rows = state.game.get_board().len();
-    state.previous_cols = state.game.get_board()[0].len();
+    let resume = std::mem::take(&mut state.resume);
+    if !resume {
+        state.game = Game::with_settings(settings.game.clone());
+        state.history.clear();
+        if state.game.power_ups_enabled {
+            debug!("Power-up layout: {}", placement::report(&state.game));
+        }
+    }
+    cursor.col = None;
+    layout.fit(&state.game);
+    commands.insert_resource(ChainReaction::default());
+    commands.insert_resource(ItemSelection::default());
 
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
-                color: Color::rgba(0.05, 0.05, 0.2, 0.5),
+                color: theme.board_backdrop,
                 custom_size: Some(Vec2::new(800.0, 600.0)),
                 ..default()
             },
-            transform: Transform::from_xyz(0.0, -50.0, -10.0),
+            transform: Transform::from_translation(layout.center().extend(-10.0)),
             ..default()
         },
         GameBackground,
@@ -379,93 +683,109 @@ fn setup_game(
     render_game_board(
         &mut commands,
         &state,
+        &layout,
         &asset_server,
         &mut meshes,
         &mut materials,
+        &theme,
+        &skin,
     );
+    if resume {
+        spawn_placed_pieces(
+            &mut commands,
+            &state.game,
+            &layout,
+            &mut meshes,
+            &mut materials,
+            &theme,
+            &skin,
+        );
+    }
 
-    let (board_width, board_height) = get_board_dimensions(&state);
-    adjust_camera(&mut camera_query, board_width, board_height);
+    adjust_camera(&mut camera_query, &layout);
 }
 
-const COLUMN_COLORS: [Color; 10] = [
-    Color::RED,
-    Color::GREEN,
-    Color::PURPLE,
-    Color::ORANGE,
-    Color::PINK,
-    Color::TEAL,
-    Color::CYAN,
-    Color::LIME_GREEN,
-    Color::INDIGO,
-    Color::VIOLET,
-];
+/// Spawns every piece already on the board at its resting position.
+fn spawn_placed_pieces(
+    commands: &mut Commands,
+    game: &Game,
+    layout: &BoardLayout,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    theme: &Theme,
+    skin: &LoadedSkin,
+) {
+    for (row, row_cells) in game.get_board().iter().enumerate() {
+        for (col, &cell) in row_cells.iter().enumerate() {
+            if cell == PLAYER_X || cell == PLAYER_O {
+                spawn_existing_piece(
+                    commands, cell, layout, row, col, meshes, materials, theme, skin, false,
+                );
+            }
+        }
+    }
+}
 
 fn render_game_board(
     commands: &mut Commands,
     state: &GameStateResource,
+    layout: &BoardLayout,
     asset_server: &Res<AssetServer>,
-    _meshes: &mut ResMut<Assets<Mesh>>,
-    _materials: &mut ResMut<Assets<ColorMaterial>>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    theme: &Theme,
+    skin: &LoadedSkin,
 ) {
-    let rows = state.game.get_board().len();
-    let cols = state.game.get_board()[0].len();
-
-    let cell_size = 75.0;
-    let padding = 7.5;
-    let board_width = cols as f32 * (cell_size + padding) - padding;
-    let board_height = rows as f32 * (cell_size + padding) - padding;
-    let board_offset_y = -50.0;
+    let rows = layout.rows();
+    let cols = layout.cols();
+    let cell_size = layout.cell_size();
 
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
-                color: Color::rgba(0.0, 0.0, 0.8, 0.9),
-                custom_size: Some(Vec2::new(
-                    board_width + padding * 2.0,
-                    board_height + padding * 2.0,
-                )),
+                color: theme.board,
+                custom_size: Some(layout.frame_size()),
                 ..default()
             },
-            transform: Transform::from_xyz(0.0, board_offset_y, 0.0),
+            transform: Transform::from_translation(layout.center().extend(0.0)),
             ..default()
         },
+        BoardFrame,
         GameUI,
     ));
 
     for col in 0..cols {
-        let label = if col < 9 {
-            (col + 1).to_string()
-        } else {
-            "0".to_string()
-        };
+        spawn_column_label(commands, col, layout, asset_server, theme);
+    }
 
-        let color = if col < COLUMN_COLORS.len() {
-            COLUMN_COLORS[col]
-        } else {
-            Color::GOLD
-        };
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(Circle::new(cell_size / 2.0 - 5.0)))
+                .into(),
+            material: materials.add(ColorMaterial::from(theme.players[0].with_a(0.35))),
+            transform: Transform::from_xyz(0.0, 0.0, 2.5),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        GhostPiece,
+        GameUI,
+    ));
 
-        commands.spawn((
-            Text2dBundle {
-                text: Text::from_section(
-                    label,
-                    TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                        font_size: 45.0,
-                        color,
-                    },
-                ),
-                transform: Transform::from_xyz(
-                    col as f32 * (cell_size + padding) - board_width / 2.0 + cell_size / 2.0,
-                    board_height / 2.0 + 40.0 + board_offset_y,
-                    1.0,
-                ),
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.players[0],
+                custom_size: Some(Vec2::new(cell_size * 0.6, 10.0)),
                 ..default()
             },
-            GameUI,
-        ));
-    }
+            transform: Transform::from_xyz(0.0, layout.selector_y(), 2.5),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ColumnSelector,
+        GameUI,
+    ));
 
     commands
         .spawn((
@@ -473,11 +793,8 @@ fn render_game_board(
                 style: Style {
                     position_type: PositionType::Absolute,
                     top: Val::Px(10.0),
-                    left: Val::Percent(50.0),
-                    margin: UiRect {
-                        left: Val::Px(-150.0),
-                        ..default()
-                    },
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
                     ..default()
                 },
                 background_color: Color::NONE.into(),
@@ -486,14 +803,15 @@ fn render_game_board(
             GameUI,
         ))
         .with_children(|parent| {
+            let (turn_text, turn_color) = turn_indicator(state.game.get_current_player(), theme);
             parent.spawn((
                 TextBundle {
                     text: Text::from_section(
-                        "Player 1's Turn",
+                        turn_text,
                         TextStyle {
-                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                            font: theme.bold_font(asset_server),
                             font_size: 50.0,
-                            color: Color::RED,
+                            color: turn_color,
                         },
                     )
                     .with_alignment(TextAlignment::Center),
@@ -501,47 +819,20 @@ fn render_game_board(
                 },
                 TurnIndicator,
             ));
+            inventory::spawn_panel(parent, asset_server, theme);
         });
+    inventory::spawn_aim_marker(commands, layout);
 
     for row in 0..rows {
         for col in 0..cols {
             let cell_char = state.game.get_board()[row][col];
-            let power_up = PowerUpType::from_char(cell_char);
+            let power_up = power_ups::registry().get(cell_char);
             let is_obstacle = cell_char == OBSTACLE;
 
-            commands.spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::rgba(0.9, 0.9, 0.9, 0.1),
-                        custom_size: Some(Vec2::new(cell_size, cell_size)),
-                        ..default()
-                    },
-                    transform: Transform::from_xyz(
-                        col as f32 * (cell_size + padding) - board_width / 2.0 + cell_size / 2.0,
-                        row as f32 * (cell_size + padding) - board_height / 2.0
-                            + cell_size / 2.0
-                            + board_offset_y,
-                        1.0,
-                    ),
-                    ..default()
-                },
-                Cell { row, col, power_up },
-                GameUI,
-            ));
+            spawn_cell(commands, row, col, power_up, layout, theme, skin);
 
             if let Some(pu) = power_up {
-                spawn_power_up_symbol(
-                    commands,
-                    row,
-                    col,
-                    pu,
-                    cell_size,
-                    padding,
-                    board_width,
-                    board_height,
-                    board_offset_y,
-                    asset_server,
-                );
+                spawn_power_up_symbol(commands, row, col, pu, layout, asset_server, theme, skin);
             }
 
             if is_obstacle {
@@ -549,46 +840,102 @@ fn render_game_board(
                     commands,
                     row,
                     col,
-                    cell_size,
-                    padding,
-                    board_width,
-                    board_height,
-                    board_offset_y,
-                    &mut Some(AnimatePiece {
-                        target_y: row as f32 * (cell_size + padding) - board_height / 2.0
-                            + cell_size / 2.0
-                            + board_offset_y,
-                    }),
+                    layout,
+                    &mut Some(AnimatePiece::to(layout.row_y(row))),
+                    theme,
+                    skin,
                 );
             }
         }
     }
 }
 
+fn spawn_column_label(
+    commands: &mut Commands,
+    col: usize,
+    layout: &BoardLayout,
+    asset_server: &AssetServer,
+    theme: &Theme,
+) -> Entity {
+    let label = (col + 1).to_string();
+    let color = theme.column_label_color(col);
+
+    commands
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font: theme.bold_font(asset_server),
+                        font_size: 45.0,
+                        color,
+                    },
+                ),
+                transform: Transform::from_xyz(layout.column_x(col), layout.label_y(), 1.0),
+                ..default()
+            },
+            ColumnLabel { col, color },
+            GameUI,
+        ))
+        .id()
+}
+
+fn spawn_cell(
+    commands: &mut Commands,
+    row: usize,
+    col: usize,
+    power_up: Option<&'static dyn PowerUp>,
+    layout: &BoardLayout,
+    theme: &Theme,
+    skin: &LoadedSkin,
+) -> Entity {
+    let cell_size = layout.cell_size();
+    let transform = Transform::from_translation(layout.cell_position(row, col).extend(1.0));
+    skin.spawn(commands, SkinElement::Cell, cell_size, transform, || {
+        SpriteBundle {
+            sprite: Sprite {
+                color: theme.cell,
+                custom_size: Some(Vec2::new(cell_size, cell_size)),
+                ..default()
+            },
+            transform,
+            ..default()
+        }
+    })
+    .insert((Cell { row, col, power_up }, GameUI))
+    .id()
+}
+
 fn spawn_power_up_symbol(
     commands: &mut Commands,
     row: usize,
     col: usize,
-    pu: PowerUpType,
-    cell_size: f32,
-    padding: f32,
-    board_width: f32,
-    board_height: f32,
-    board_offset_y: f32,
+    pu: &'static dyn PowerUp,
+    layout: &BoardLayout,
     asset_server: &Res<AssetServer>,
+    theme: &Theme,
+    skin: &LoadedSkin,
 ) {
-    let x = col as f32 * (cell_size + padding) - board_width / 2.0 + cell_size / 2.0;
-    let y =
-        row as f32 * (cell_size + padding) - board_height / 2.0 + cell_size / 2.0 + board_offset_y;
+    let cell_size = layout.cell_size();
+    let position = layout.cell_position(row, col);
+
+    // Icons already say what they are, so only flat symbols get a letter.
+    let element = SkinElement::PowerUp(pu.id());
+    if skin.is_textured(element) {
+        let transform = Transform::from_translation(position.extend(1.5));
+        skin.spawn(commands, element, cell_size * 0.6, transform, || ())
+            .insert((PowerUpSymbol { row, col }, GameUI));
+        return;
+    }
 
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
-                color: pu.color(),
+                color: theme.power_up_color(pu),
                 custom_size: Some(Vec2::new(cell_size / 2.0, cell_size / 2.0)),
                 ..default()
             },
-            transform: Transform::from_xyz(x, y, 1.5),
+            transform: Transform::from_translation(position.extend(1.5)),
             ..default()
         },
         PowerUpSymbol { row, col },
@@ -598,14 +945,14 @@ fn spawn_power_up_symbol(
     commands.spawn((
         Text2dBundle {
             text: Text::from_section(
-                pu.label(),
+                pu.visual().label.clone(),
                 TextStyle {
-                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font: theme.bold_font(asset_server),
                     font_size: 30.0,
-                    color: Color::WHITE,
+                    color: theme.text,
                 },
             ),
-            transform: Transform::from_xyz(x, y, 2.0),
+            transform: Transform::from_translation(position.extend(2.0)),
             ..default()
         },
         PowerUpSymbol { row, col },
@@ -617,164 +964,681 @@ fn spawn_obstacle(
     commands: &mut Commands,
     row: usize,
     col: usize,
-    cell_size: f32,
-    padding: f32,
-    board_width: f32,
-    board_height: f32,
-    board_offset_y: f32,
+    layout: &BoardLayout,
     animate_piece: &mut Option<AnimatePiece>,
-) {
+    theme: &Theme,
+    skin: &LoadedSkin,
+) -> Entity {
     let z = 2.0;
+    let cell_size = layout.cell_size();
     let initial_y = if animate_piece.is_some() {
-        board_height / 2.0 + cell_size + board_offset_y
+        layout.drop_y()
     } else {
-        row as f32 * (cell_size + padding) - board_height / 2.0 + cell_size / 2.0 + board_offset_y
+        layout.row_y(row)
     };
 
-    let x = col as f32 * (cell_size + padding) - board_width / 2.0 + cell_size / 2.0;
+    let transform = Transform::from_xyz(layout.column_x(col), initial_y, z);
 
-    let transform = Transform::from_xyz(x, initial_y, z);
-
-    let mut entity_commands = commands.spawn((
-        SpriteBundle {
+    let mut entity_commands = skin.spawn(
+        commands,
+        SkinElement::Obstacle,
+        cell_size,
+        transform,
+        || SpriteBundle {
             sprite: Sprite {
-                color: Color::BLACK,
+                color: theme.obstacle,
                 custom_size: Some(Vec2::new(cell_size, cell_size)),
                 ..default()
             },
             transform,
             ..default()
         },
-        StaticObstacle { row, col },
-        GameUI,
-    ));
+    );
+    entity_commands.insert((StaticObstacle { row, col }, GameUI));
 
     if let Some(animate) = animate_piece.take() {
         entity_commands.insert(animate);
     }
+    entity_commands.id()
 }
 
 fn update_game(
     mut state: ResMut<GameStateResource>,
-    keyboard_input: Res<Input<KeyCode>>,
+    layout: Res<BoardLayout>,
+    mut drop_requests: EventReader<DropRequest>,
+    mut target_requests: EventReader<TargetRequest>,
     mut app_state: ResMut<NextState<AppState>>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut turn_query: Query<&mut Text, With<TurnIndicator>>,
+    mut chain: ResMut<ChainReaction>,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
+    mut sounds: EventWriter<PlaySound>,
+    mut bursts: EventWriter<ParticleBurst>,
+    mut spawned: EventWriter<PowerUpSpawned>,
+    mut selection: ResMut<ItemSelection>,
+) {
+    // Only one piece is dropped per frame; extra requests from rapid input are ignored.
+    let requested_col = drop_requests.iter().next().map(|request| request.col);
+    drop_requests.clear();
+    let requested_target = target_requests.iter().next().map(|request| request.cell);
+    target_requests.clear();
+
+    // While a power-up waits for its target, requests pick or cancel it.
+    if let Some(&targeting) = state.game.targeting() {
+        let Some(cell) = requested_target.or(requested_col.map(|col| Some((selection.row, col))))
+        else {
+            return;
+        };
+        match cell {
+            Some((row, col)) => match state.game.choose_target(row, col) {
+                Ok(activation) => queue_power_ups(&mut chain, vec![activation]),
+                Err(err) => {
+                    println!("{}", err);
+                    sounds.send(PlaySound(SoundEffect::Invalid));
+                    return;
+                }
+            },
+            None => state.game.cancel_target(),
+        }
+
+        if state.game.check_winner().is_some() {
+            sounds.send(PlaySound(SoundEffect::Win));
+            particles::win_confetti(&layout, &mut bursts);
+        } else if targeting.ends_turn {
+            selection.clear();
+            end_turn(
+                &mut state.game,
+                &mut app_state,
+                &mut sounds,
+                &mut spawned,
+                &mut turn_query,
+                &theme,
+            );
+        }
+        return;
+    }
+
+    let Some(col) = requested_col else {
+        return;
+    };
+
+    let snapshot = state.game.clone();
+
+    // With a collected power-up chosen, the request aims it instead.
+    if let Some(glyph) = selection.glyph(&state.game) {
+        selection.clear();
+        match state.game.use_power_up(glyph, selection.row, col) {
+            Ok(activations) => {
+                state.history.push(snapshot);
+                queue_power_ups(&mut chain, activations);
+                if state.game.check_winner().is_some() {
+                    sounds.send(PlaySound(SoundEffect::Win));
+                    particles::win_confetti(&layout, &mut bursts);
+                }
+            }
+            Err(err) => {
+                println!("{}", err);
+                sounds.send(PlaySound(SoundEffect::Invalid));
+            }
+        }
+        return;
+    }
+
+    if let Ok(landing) = state.game.drop_piece(col) {
+        let (row, col) = (landing.row, landing.col);
+        state.history.push(snapshot);
+        let player = state.game.get_current_player();
+        sounds.send(PlaySound(SoundEffect::Drop));
+
+        spawn_piece(
+            &mut commands,
+            player,
+            row,
+            col,
+            &mut meshes,
+            &mut materials,
+            &layout,
+            &theme,
+            &skin,
+        );
+
+        queue_power_ups(&mut chain, landing.power_ups);
+
+        if let Some(_winner) = state.game.check_winner() {
+            // `finish_won_game` moves on once the celebration is over.
+            sounds.send(PlaySound(SoundEffect::Win));
+            particles::win_confetti(&layout, &mut bursts);
+            return;
+        }
+
+        // The turn ends once the player has picked a target.
+        if let Some(targeting) = state.game.targeting() {
+            selection.row = targeting.row;
+            return;
+        }
+
+        selection.clear();
+        end_turn(
+            &mut state.game,
+            &mut app_state,
+            &mut sounds,
+            &mut spawned,
+            &mut turn_query,
+            &theme,
+        );
+    } else {
+        println!("Column is full.");
+        sounds.send(PlaySound(SoundEffect::Invalid));
+    }
+}
+
+/// Grows or ties a full board, then passes play on and lets a power-up
+/// respawn.
+fn end_turn(
+    game: &mut Game,
+    app_state: &mut NextState<AppState>,
+    sounds: &mut EventWriter<PlaySound>,
+    spawned: &mut EventWriter<PowerUpSpawned>,
+    turn_query: &mut Query<&mut Text, With<TurnIndicator>>,
+    theme: &Theme,
+) {
+    if game.is_full() {
+        if game.can_expand() {
+            // The view catches up in `expand_board_view` once the piece
+            // has landed.
+            game.expand_board();
+            game.expanded = true;
+        } else {
+            sounds.send(PlaySound(SoundEffect::Tie));
+            app_state.set(AppState::GameOver);
+            return;
+        }
+    }
+
+    game.switch_player();
+    for (row, col) in game.respawn_power_ups() {
+        if let Some(power_up) = power_ups::registry().get(game.get_board()[row][col]) {
+            spawned.send(PowerUpSpawned { col, power_up });
+        }
+    }
+
+    let (turn_text, turn_color) = turn_indicator(game.get_current_player(), theme);
+    for mut text in turn_query {
+        text.sections[0].value = turn_text.clone();
+        text.sections[0].style.color = turn_color;
+    }
+}
+
+/// Lines up power-ups that went off to be shown one after another.
+fn queue_power_ups(chain: &mut ChainReaction, activations: Vec<Activation>) {
+    for activation in activations {
+        if let Some(power_up) = power_ups::registry().get(activation.glyph) {
+            chain.pending.push_back(PowerUpActivated {
+                row: activation.row,
+                col: activation.col,
+                power_up,
+                effects: activation.effects,
+            });
+        }
+    }
+}
+
+fn keyboard_column_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    state: Res<GameStateResource>,
+    mut drop_requests: EventWriter<DropRequest>,
+) {
+    let cols = state.game.get_board()[0].len();
+    let player = state.game.get_current_player();
+
+    for col in 0..cols.min(controls::COLUMN_KEY_COUNT) {
+        if input_map.player_just_pressed(player, Action::DropColumn(col), &keyboard_input) {
+            drop_requests.send(DropRequest { col });
+            break;
+        }
+    }
+}
+
+fn keyboard_cursor_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    state: Res<GameStateResource>,
+    mut cursor: ResMut<ColumnCursor>,
+    mut drop_requests: EventWriter<DropRequest>,
+) {
+    let cols = state.game.get_board()[0].len();
+    let player = state.game.get_current_player();
+
+    if input_map.player_just_pressed(player, Action::CursorLeft, &keyboard_input) {
+        cursor.step(-1, cols);
+    } else if input_map.player_just_pressed(player, Action::CursorRight, &keyboard_input) {
+        cursor.step(1, cols);
+    }
+
+    if input_map.player_just_pressed(player, Action::Drop, &keyboard_input) {
+        if let Some(col) = cursor.col {
+            drop_requests.send(DropRequest { col });
+        }
+    }
+}
+
+/// Run condition: the player to move is sitting at the keyboard, mouse or
+/// controller rather than being played by the computer.
+fn human_turn(settings: Res<Settings>, state: Res<GameStateResource>) -> bool {
+    settings.seat(state.game.get_current_player()) == Seat::Human
+}
+
+/// Run condition: nobody has won yet, so moves, undo and pausing are allowed.
+fn game_in_progress(state: Res<GameStateResource>) -> bool {
+    state.game.check_winner().is_none()
+}
+
+/// How long the confetti falls over the board before the game over screen.
+const CELEBRATION_TIME: f32 = 2.0;
+
+fn finish_won_game(
+    time: Res<Time>,
+    state: Res<GameStateResource>,
+    mut waited: Local<f32>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if state.game.check_winner().is_none() {
+        *waited = 0.0;
+        return;
+    }
+
+    *waited += time.delta_seconds();
+    if *waited >= CELEBRATION_TIME {
+        *waited = 0.0;
+        app_state.set(AppState::GameOver);
+    }
+}
+
+const COMPUTER_MOVE_DELAY: f32 = 0.6;
+
+/// Plays for computer seats, waiting for falling pieces to land and a short
+/// pause so the move can be followed.
+fn computer_turn(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    state: Res<GameStateResource>,
+    falling_pieces: Query<(), With<AnimatePiece>>,
+    mut waited: Local<f32>,
+    mut drop_requests: EventWriter<DropRequest>,
+    mut target_requests: EventWriter<TargetRequest>,
+) {
+    let Seat::Computer(difficulty) = settings.seat(state.game.get_current_player()) else {
+        *waited = 0.0;
+        return;
+    };
+    if !falling_pieces.is_empty() {
+        return;
+    }
+
+    *waited += time.delta_seconds();
+    if *waited < COMPUTER_MOVE_DELAY {
+        return;
+    }
+    *waited = 0.0;
+
+    if state.game.targeting().is_some() {
+        let cell = ai::choose_target(&state.game);
+        target_requests.send(TargetRequest { cell });
+    } else if let Some(col) = ai::choose_column(&state.game, difficulty) {
+        drop_requests.send(DropRequest { col });
+    }
+}
+
+fn undo_move(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    settings: Res<Settings>,
+    mut state: ResMut<GameStateResource>,
+    mut layout: ResMut<BoardLayout>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
     asset_server: Res<AssetServer>,
     game_ui_query: Query<Entity, With<GameUI>>,
-    mut camera_query: Query<(&mut OrthographicProjection, &mut Transform), With<MainCamera>>,
-    mut power_up_activated_events: EventWriter<PowerUpActivated>,
+    mut camera_query: MainCameraQuery,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
 ) {
-    let cols = state.game.get_board()[0].len();
+    if !input_map.just_pressed(Action::Undo, &keyboard_input) {
+        return;
+    }
+    let Some(mut previous) = state.history.pop() else {
+        return;
+    };
 
-    for col in 0..cols {
-        let key = match col {
-            0 => KeyCode::Key1,
-            1 => KeyCode::Key2,
-            2 => KeyCode::Key3,
-            3 => KeyCode::Key4,
-            4 => KeyCode::Key5,
-            5 => KeyCode::Key6,
-            6 => KeyCode::Key7,
-            7 => KeyCode::Key8,
-            8 => KeyCode::Key9,
-            9 => KeyCode::Key0,
-            _ => continue,
+    // Step back over the computer's replies so undo lands on a human's turn.
+    if settings.seats.contains(&Seat::Human) {
+        while settings.seat(previous.get_current_player()) != Seat::Human {
+            match state.history.pop() {
+                Some(earlier) => previous = earlier,
+                None => break,
+            }
+        }
+    }
+
+    // Undoing can shrink the board again, so rebuild the whole view.
+    state.game = previous;
+    rebuild_board_view(
+        &mut commands,
+        &state,
+        &mut layout,
+        &asset_server,
+        &mut meshes,
+        &mut materials,
+        &game_ui_query,
+        &mut camera_query,
+        &theme,
+        &skin,
+    );
+}
+
+/// Replaces every board entity with a fresh render of the current game, with
+/// pieces already resting in place.
+fn rebuild_board_view(
+    commands: &mut Commands,
+    state: &GameStateResource,
+    layout: &mut BoardLayout,
+    asset_server: &Res<AssetServer>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    game_ui_query: &Query<Entity, With<GameUI>>,
+    camera_query: &mut MainCameraQuery,
+    theme: &Theme,
+    skin: &LoadedSkin,
+) {
+    cleanup_game_board(commands, game_ui_query);
+    layout.fit(&state.game);
+    render_game_board(
+        commands,
+        state,
+        layout,
+        asset_server,
+        meshes,
+        materials,
+        theme,
+        skin,
+    );
+    spawn_placed_pieces(
+        commands,
+        &state.game,
+        layout,
+        meshes,
+        materials,
+        theme,
+        skin,
+    );
+
+    adjust_camera(camera_query, layout);
+}
+
+fn quick_save(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    state: Res<GameStateResource>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+) {
+    if input_map.just_pressed(Action::Save, &keyboard_input) {
+        save_game(&mut commands, &asset_server, &theme, &state.game);
+    }
+}
+
+fn save_game(commands: &mut Commands, asset_server: &AssetServer, theme: &Theme, game: &Game) {
+    match storage::save_ron(SAVE_FILE, game) {
+        Ok(()) => spawn_toast(commands, asset_server, theme, "Game saved."),
+        Err(err) => {
+            eprintln!("Could not save game: {}", err);
+            spawn_toast(commands, asset_server, theme, "Could not save the game.");
+        }
+    }
+}
+
+fn pause_game(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+) {
+    let start_pressed = gamepad_buttons
+        .get_just_pressed()
+        .any(|button| button.button_type == GamepadButtonType::Start);
+
+    if input_map.just_pressed(Action::Pause, &keyboard_input) || start_pressed {
+        pause_state.set(PauseState::Paused);
+    }
+}
+
+/// Large centred message that fades like a toast.
+fn spawn_banner(commands: &mut Commands, asset_server: &AssetServer, theme: &Theme, message: &str) {
+    commands.spawn((
+        TextBundle::from_section(
+            message,
+            TextStyle {
+                font: theme.bold_font(asset_server),
+                font_size: 60.0,
+                color: theme.title,
+            },
+        )
+        .with_text_alignment(TextAlignment::Center)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(12.0),
+            width: Val::Percent(100.0),
+            ..default()
+        }),
+        Toast {
+            timer: Timer::from_seconds(2.0, TimerMode::Once),
+        },
+        GameUI,
+    ));
+}
+
+fn spawn_toast(commands: &mut Commands, asset_server: &AssetServer, theme: &Theme, message: &str) {
+    commands.spawn((
+        TextBundle::from_section(
+            message,
+            TextStyle {
+                font: theme.bold_font(asset_server),
+                font_size: 28.0,
+                color: theme.text,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            right: Val::Px(20.0),
+            ..default()
+        }),
+        Toast {
+            timer: Timer::from_seconds(2.0, TimerMode::Once),
+        },
+    ));
+}
+
+/// Tells the players about power-ups that respawned; `pop_in_power_ups`
+/// grows them into place.
+fn announce_spawned_power_ups(
+    mut spawned: EventReader<PowerUpSpawned>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+) {
+    // A mirrored pair arrives together, so it gets one toast.
+    let events: Vec<&PowerUpSpawned> = spawned.iter().collect();
+    let Some(first) = events.first() else {
+        return;
+    };
+    let message = if events.len() == 1 {
+        format!(
+            "A {} power-up appeared in column {}!",
+            first.power_up.name(),
+            first.col + 1
+        )
+    } else {
+        let columns: Vec<String> = events
+            .iter()
+            .map(|event| (event.col + 1).to_string())
+            .collect();
+        let kind = if events
+            .iter()
+            .all(|event| event.power_up.glyph() == first.power_up.glyph())
+        {
+            first.power_up.name()
+        } else {
+            "New"
         };
+        format!(
+            "{} power-ups appeared in columns {}!",
+            kind,
+            columns.join(" and ")
+        )
+    };
+    spawn_toast(&mut commands, &asset_server, &theme, &message);
+}
 
-        if keyboard_input.just_pressed(key) {
-            if let Ok((row, col)) = state.game.drop_piece(col) {
-                let player = state.game.get_current_player();
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Toast, &mut Text)>,
+) {
+    for (entity, mut toast, mut text) in &mut query {
+        toast.timer.tick(time.delta());
+        let alpha = (1.0 - toast.timer.percent()).min(0.5) * 2.0;
+        text.sections[0].style.color.set_a(alpha);
 
-                if state.game.is_full() {
-                    if !state.game.expanded {
-                        state.game.expand_board();
-                        state.game.expanded = true;
-                        state.game.switch_player();
+        if toast.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
 
-                        cleanup_game_board(&mut commands, &game_ui_query);
-                        render_game_board(
-                            &mut commands,
-                            &state,
-                            &asset_server,
-                            &mut meshes,
-                            &mut materials,
-                        );
+fn mouse_column_input(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mouse_input: Res<Input<MouseButton>>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    layout: Res<BoardLayout>,
+    mut cursor: ResMut<ColumnCursor>,
+    mut drop_requests: EventWriter<DropRequest>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
 
-                        let (board_width, board_height) = get_board_dimensions(&state);
-                        adjust_camera(&mut camera_query, board_width, board_height);
-
-                        for row in 0..state.game.get_board().len() {
-                            for col in 0..state.game.get_board()[0].len() {
-                                let cell_char = state.game.get_board()[row][col];
-                                if cell_char == PLAYER_X
-                                    || cell_char == PLAYER_O
-                                    || cell_char == OBSTACLE
-                                {
-                                    spawn_existing_piece(
-                                        &mut commands,
-                                        &state.game,
-                                        row,
-                                        col,
-                                        &mut meshes,
-                                        &mut materials,
-                                    );
-                                }
-                            }
-                        }
-                        return;
-                    } else {
-                        app_state.set(AppState::GameOver);
-                        return;
-                    }
-                } else {
-                    spawn_piece(
-                        &mut commands,
-                        player,
-                        row,
-                        col,
-                        &mut meshes,
-                        &mut materials,
-                        &state,
-                    );
-                }
+    // The cursor is in window pixels, so it has to go through the camera's
+    // orthographic projection set up by `adjust_camera` to land in board space.
+    let hovered_col = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        .and_then(|world| layout.column_at(world));
+
+    // Only follow the mouse while it moves, so a resting pointer does not
+    // fight the keyboard cursor.
+    if !cursor_moved.is_empty() {
+        cursor_moved.clear();
+        if cursor.col != hovered_col {
+            cursor.col = hovered_col;
+        }
+    }
+
+    if let Some(col) = hovered_col {
+        if mouse_input.just_pressed(MouseButton::Left) {
+            drop_requests.send(DropRequest { col });
+        }
+    }
+}
+
+fn update_ghost_piece(
+    state: Res<GameStateResource>,
+    layout: Res<BoardLayout>,
+    cursor: Res<ColumnCursor>,
+    selection: Res<ItemSelection>,
+    mut ghost_query: Query<
+        (&mut Transform, &mut Visibility, &Handle<ColorMaterial>),
+        With<GhostPiece>,
+    >,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    theme: Res<Theme>,
+) {
+    let Ok((mut transform, mut visibility, material)) = ghost_query.get_single_mut() else {
+        return;
+    };
+
+    // While a power-up is aimed, the aim marker shows where it goes instead.
+    let landing = cursor
+        .col
+        .filter(|_| selection.glyph(&state.game).is_none() && state.game.targeting().is_none())
+        .and_then(|col| state.game.landing_row(col).map(|row| (row, col)));
+
+    let Some((row, col)) = landing else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    transform.translation.x = layout.column_x(col);
+    transform.translation.y = layout.row_y(row);
+    *visibility = Visibility::Visible;
+
+    if let Some(material) = materials.get_mut(material) {
+        material.color = theme
+            .player_color(state.game.get_current_player())
+            .with_a(0.35);
+    }
+}
+
+fn update_column_selector(
+    state: Res<GameStateResource>,
+    layout: Res<BoardLayout>,
+    cursor: Res<ColumnCursor>,
+    mut selector_query: Query<(&mut Transform, &mut Visibility, &mut Sprite), With<ColumnSelector>>,
+    theme: Res<Theme>,
+) {
+    let Ok((mut transform, mut visibility, mut sprite)) = selector_query.get_single_mut() else {
+        return;
+    };
 
-                let cell_char = state.game.get_board()[row][col];
-                if let Some(pu) = PowerUpType::from_char(cell_char) {
-                    power_up_activated_events.send(PowerUpActivated {
-                        row,
-                        col,
-                        power_up: pu,
-                    });
-                }
+    let Some(col) = cursor.col else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
 
-                if let Some(_winner) = state.game.check_winner() {
-                    app_state.set(AppState::GameOver);
-                    return;
-                }
+    transform.translation.x = layout.column_x(col);
+    transform.translation.y = layout.selector_y();
+    *visibility = Visibility::Visible;
 
-                state.game.switch_player();
+    sprite.color = theme.player_color(state.game.get_current_player());
+}
 
-                for mut text in &mut turn_query {
-                    let player_number = if state.game.get_current_player() == PLAYER_X {
-                        "1"
-                    } else {
-                        "2"
-                    };
-                    text.sections[0].value = format!("Player {}'s Turn", player_number);
-                    text.sections[0].style.color = if state.game.get_current_player() == PLAYER_X {
-                        Color::RED
-                    } else {
-                        Color::YELLOW
-                    };
-                }
-            } else {
-                println!("Column is full.");
-            }
+fn highlight_column_label(
+    cursor: Res<ColumnCursor>,
+    new_labels: Query<(), Added<ColumnLabel>>,
+    mut label_query: Query<(&ColumnLabel, &mut Text, &mut Transform)>,
+    theme: Res<Theme>,
+) {
+    if !cursor.is_changed() && new_labels.is_empty() {
+        return;
+    }
 
-            break;
+    for (label, mut text, mut transform) in &mut label_query {
+        if cursor.col == Some(label.col) {
+            text.sections[0].style.color = theme.text;
+            transform.scale = Vec3::splat(1.3);
+        } else {
+            text.sections[0].style.color = label.color;
+            transform.scale = Vec3::ONE;
         }
     }
 }
@@ -786,43 +1650,33 @@ fn spawn_piece(
     col: usize,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
-    state: &GameStateResource,
+    layout: &BoardLayout,
+    theme: &Theme,
+    skin: &LoadedSkin,
 ) {
     if player == PLAYER_X || player == PLAYER_O {
-        let (color, z) = if player == PLAYER_X {
-            (Color::RED, 1.9)
-        } else {
-            (Color::YELLOW, 1.9)
-        };
+        let (color, z) = (theme.player_color(player), 1.9);
+        let cell_size = layout.cell_size();
 
-        let cell_size = 75.0;
-        let padding = 7.5;
-        let cols = state.game.get_board()[0].len();
-        let rows = state.game.get_board().len();
-        let board_width = cols as f32 * (cell_size + padding) - padding;
-        let board_height = rows as f32 * (cell_size + padding) - padding;
-        let board_offset_y = -50.0;
-
-        let circle_mesh = meshes.add(Mesh::from(Circle::new(cell_size / 2.0 - 5.0)));
-        let material_handle = materials.add(ColorMaterial::from(color));
-
-        commands.spawn((
-            MaterialMesh2dBundle {
-                mesh: circle_mesh.into(),
-                material: material_handle,
-                transform: Transform::from_xyz(
-                    col as f32 * (cell_size + padding) - board_width / 2.0 + cell_size / 2.0,
-                    board_height / 2.0 + cell_size + board_offset_y,
-                    z,
-                ),
+        let transform = Transform::from_xyz(layout.column_x(col), layout.drop_y(), z);
+
+        skin.spawn(
+            commands,
+            SkinElement::Piece(player),
+            cell_size - 10.0,
+            transform,
+            || MaterialMesh2dBundle {
+                mesh: meshes
+                    .add(Mesh::from(Circle::new(cell_size / 2.0 - 5.0)))
+                    .into(),
+                material: materials.add(ColorMaterial::from(color)),
+                transform,
                 ..default()
             },
+        )
+        .insert((
             Piece { player, row, col },
-            AnimatePiece {
-                target_y: row as f32 * (cell_size + padding) - board_height / 2.0
-                    + cell_size / 2.0
-                    + board_offset_y,
-            },
+            AnimatePiece::to(layout.row_y(row)),
             GameUI,
         ));
     }
@@ -830,84 +1684,324 @@ fn spawn_piece(
 
 fn spawn_existing_piece(
     commands: &mut Commands,
-    game: &Game,
+    cell_char: char,
+    layout: &BoardLayout,
     row: usize,
     col: usize,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
-) {
-    let cell_char = game.get_board()[row][col];
-    let cell_size = 75.0;
-    let padding = 7.5;
-    let cols = game.get_board()[0].len();
-    let rows = game.get_board().len();
-    let board_width = cols as f32 * (cell_size + padding) - padding;
-    let board_height = rows as f32 * (cell_size + padding) - padding;
-    let board_offset_y = -50.0;
-    let initial_y = board_height / 2.0 + cell_size + board_offset_y;
-    let target_y =
-        row as f32 * (cell_size + padding) - board_height / 2.0 + cell_size / 2.0 + board_offset_y;
-
-    let x_position = col as f32 * (cell_size + padding) - board_width / 2.0 + cell_size / 2.0;
+    theme: &Theme,
+    skin: &LoadedSkin,
+    animate: bool,
+) -> Option<Entity> {
+    let cell_size = layout.cell_size();
+    let target_y = layout.row_y(row);
+    let initial_y = if animate { layout.drop_y() } else { target_y };
 
     if cell_char == PLAYER_X || cell_char == PLAYER_O {
-        let color = if cell_char == PLAYER_X {
-            Color::RED
-        } else {
-            Color::YELLOW
-        };
+        let color = theme.player_color(cell_char);
 
-        let circle_mesh = meshes.add(Mesh::from(Circle::new(cell_size / 2.0 - 5.0)));
-        let material_handle = materials.add(ColorMaterial::from(color));
+        let transform = Transform::from_xyz(layout.column_x(col), initial_y, 1.9);
 
-        commands.spawn((
-            MaterialMesh2dBundle {
-                mesh: circle_mesh.into(),
-                material: material_handle,
-                transform: Transform::from_xyz(x_position, initial_y, 1.9),
+        let mut piece = skin.spawn(
+            commands,
+            SkinElement::Piece(cell_char),
+            cell_size - 10.0,
+            transform,
+            || MaterialMesh2dBundle {
+                mesh: meshes
+                    .add(Mesh::from(Circle::new(cell_size / 2.0 - 5.0)))
+                    .into(),
+                material: materials.add(ColorMaterial::from(color)),
+                transform,
                 ..default()
             },
+        );
+        piece.insert((
             Piece {
                 player: cell_char,
                 row,
                 col,
             },
-            AnimatePiece { target_y },
             GameUI,
         ));
+
+        if animate {
+            piece.insert(AnimatePiece::to(target_y));
+        }
+        return Some(piece.id());
     }
 
     if cell_char == OBSTACLE {
-        spawn_obstacle(
-            commands,
-            row,
-            col,
-            cell_size,
-            padding,
-            board_width,
-            board_height,
-            board_offset_y,
-            &mut None,
-        );
+        return Some(spawn_obstacle(
+            commands, row, col, layout, &mut None, theme, skin,
+        ));
     }
+    None
 }
 
 fn animate_pieces(
-    mut query: Query<(Entity, &mut Transform, &AnimatePiece)>,
+    mut query: Query<(
+        Entity,
+        &mut Transform,
+        &mut AnimatePiece,
+        Option<&StaticObstacle>,
+    )>,
     time: Res<Time>,
+    preferences: Res<config::Preferences>,
     mut commands: Commands,
+    mut landings: EventWriter<PieceLanded>,
 ) {
-    for (entity, mut transform, animate) in &mut query {
-        let speed = 800.0;
-        transform.translation.y -= speed * time.delta_seconds();
+    // Scaling time rather than speeds keeps the whole motion in proportion.
+    let dt = time.delta_seconds() * preferences.animation_speed;
+
+    for (entity, mut transform, mut animate, obstacle) in &mut query {
+        let translation = &mut transform.translation;
+
+        if preferences.reduced_motion {
+            translation.y = (translation.y - STEADY_FALL_SPEED * dt).max(animate.target_y);
+            if translation.y <= animate.target_y {
+                transform.scale = Vec3::ONE;
+                commands.entity(entity).remove::<AnimatePiece>();
+                landings.send(PieceLanded {
+                    impact: STEADY_FALL_SPEED / FULL_IMPACT_SPEED,
+                    position: transform.translation.truncate(),
+                    obstacle: obstacle.is_some(),
+                });
+            }
+            continue;
+        }
+
+        let resting = animate.landed && animate.velocity == 0.0;
+        if !resting {
+            // Exact for constant acceleration, so the path doesn't depend on
+            // the frame rate.
+            translation.y += animate.velocity * dt - 0.5 * GRAVITY * dt * dt;
+            animate.velocity -= GRAVITY * dt;
+
+            if translation.y <= animate.target_y {
+                translation.y = animate.target_y;
+                let speed = -animate.velocity;
+                let impact = (speed / FULL_IMPACT_SPEED).min(1.0);
+                if !animate.landed {
+                    animate.landed = true;
+                    landings.send(PieceLanded {
+                        impact,
+                        position: translation.truncate(),
+                        obstacle: obstacle.is_some(),
+                    });
+                }
+                animate.squash = animate.squash.max(impact * MAX_SQUASH);
+                animate.velocity = if speed * BOUNCE < SETTLE_SPEED {
+                    0.0
+                } else {
+                    speed * BOUNCE
+                };
+            }
+        }
 
-        if transform.translation.y <= animate.target_y {
-            transform.translation.y = animate.target_y;
+        animate.squash = (animate.squash - MAX_SQUASH * dt / SQUASH_TIME).max(0.0);
+        transform.scale = animate.scale();
+
+        if resting && animate.squash == 0.0 {
+            transform.scale = Vec3::ONE;
             commands.entity(entity).remove::<AnimatePiece>();
         }
     }
 }
 
+/// Grows the view after the game board has expanded, once the last piece has
+/// landed: everything already on the board slides to its new place while the
+/// frame grows and the camera zooms out, and the new cells fade in.
+fn expand_board_view(
+    mut commands: Commands,
+    state: Res<GameStateResource>,
+    mut layout: ResMut<BoardLayout>,
+    falling_pieces: Query<(), With<AnimatePiece>>,
+    board_items: Query<
+        (Entity, &Transform),
+        (
+            Or<(
+                With<Cell>,
+                With<Piece>,
+                With<StaticObstacle>,
+                With<PowerUpSymbol>,
+            )>,
+            Without<MainCamera>,
+        ),
+    >,
+    labels: Query<(Entity, &Transform), (With<ColumnLabel>, Without<MainCamera>)>,
+    mut frames: Query<(Entity, &mut Sprite), With<BoardFrame>>,
+    mut camera_query: MainCameraQuery,
+    camera_entities: Query<Entity, With<MainCamera>>,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
+    preferences: Res<config::Preferences>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    if layout.fits(&state.game) || !falling_pieces.is_empty() {
+        return;
+    }
+
+    let old_layout = *layout;
+    layout.fit(&state.game);
+    let duration = if preferences.reduced_motion {
+        0.0
+    } else {
+        EXPANSION_TIME
+    };
+
+    // The board stays centred, so everything on it moves by the same amount.
+    let shift = ((old_layout.board_size() - layout.board_size()) / 2.0).extend(0.0);
+    for (entity, transform) in &board_items {
+        let to = transform.with_translation(transform.translation + shift);
+        commands
+            .entity(entity)
+            .insert(Tween::new(*transform, to, duration, ease_in_out));
+    }
+    for (entity, transform) in &labels {
+        let to =
+            transform.with_translation(transform.translation + shift * Vec3::new(1.0, -1.0, 1.0));
+        commands
+            .entity(entity)
+            .insert(Tween::new(*transform, to, duration, ease_in_out));
+    }
+
+    for (entity, mut sprite) in &mut frames {
+        let new_size = layout.frame_size();
+        sprite.custom_size = Some(new_size);
+        let to = Transform::from_translation(layout.center().extend(0.0));
+        let from = to.with_scale((old_layout.frame_size() / new_size).extend(1.0));
+        commands
+            .entity(entity)
+            .insert(Tween::new(from, to, duration, ease_in_out));
+    }
+
+    // Zoom out by starting the camera scaled to the old view.
+    adjust_camera(&mut camera_query, &layout);
+    let zoom = old_layout.zoom() / layout.zoom();
+    for camera in &camera_entities {
+        let Ok((_, transform)) = camera_query.get(camera) else {
+            continue;
+        };
+        let to = transform.with_scale(Vec3::ONE);
+        let from = to.with_scale(Vec3::new(zoom, zoom, 1.0));
+        commands
+            .entity(camera)
+            .insert(Tween::new(from, to, duration, ease_in_out));
+    }
+
+    let board = state.game.get_board();
+    for (row, row_cells) in board.iter().enumerate() {
+        for (col, &cell) in row_cells.iter().enumerate() {
+            if row < old_layout.rows() && col < old_layout.cols() {
+                continue;
+            }
+            let cell = spawn_cell(
+                &mut commands,
+                row,
+                col,
+                power_ups::registry().get(cell),
+                &layout,
+                &theme,
+                &skin,
+            );
+            FadeIn::insert(&mut commands, cell, duration);
+        }
+    }
+    for col in old_layout.cols()..layout.cols() {
+        let label = spawn_column_label(&mut commands, col, &layout, &asset_server, &theme);
+        FadeIn::insert(&mut commands, label, duration);
+    }
+
+    spawn_banner(&mut commands, &asset_server, &theme, "Board expanded!");
+    sounds.send(PlaySound(SoundEffect::Expand));
+}
+
+/// New power-ups grow into place; `synchronize_frontend` spawns them.
+fn pop_in_power_ups(
+    mut commands: Commands,
+    new_power_ups: Query<(Entity, &Transform), Added<PowerUpSymbol>>,
+    preferences: Res<config::Preferences>,
+) {
+    if preferences.reduced_motion {
+        return;
+    }
+    for (entity, transform) in &new_power_ups {
+        let from = transform.with_scale(Vec3::ZERO);
+        commands
+            .entity(entity)
+            .insert(Tween::new(from, *transform, POP_IN_TIME, ease_out_back));
+    }
+}
+
+fn animate_tweens(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut Tween)>,
+    time: Res<Time>,
+    preferences: Res<config::Preferences>,
+) {
+    let dt = time.delta_seconds() * preferences.animation_speed;
+    for (entity, mut transform, mut tween) in &mut query {
+        tween.elapsed += dt;
+        let progress = if tween.duration > 0.0 {
+            (tween.elapsed / tween.duration).min(1.0)
+        } else {
+            1.0
+        };
+        let eased = (tween.ease)(progress);
+        transform.translation = tween.from.translation.lerp(tween.to.translation, eased);
+        transform.scale = tween.from.scale.lerp(tween.to.scale, eased);
+
+        if progress >= 1.0 {
+            commands.entity(entity).remove::<Tween>();
+        }
+    }
+}
+
+fn animate_fade_ins(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut FadeIn,
+        &mut Visibility,
+        Option<&mut Sprite>,
+        Option<&mut TextureAtlasSprite>,
+        Option<&mut Text>,
+    )>,
+    time: Res<Time>,
+    preferences: Res<config::Preferences>,
+) {
+    let dt = time.delta_seconds() * preferences.animation_speed;
+    for (entity, mut fade, mut visibility, sprite, atlas_sprite, text) in &mut query {
+        let color = if let Some(sprite) = sprite {
+            &mut sprite.into_inner().color
+        } else if let Some(atlas_sprite) = atlas_sprite {
+            &mut atlas_sprite.into_inner().color
+        } else if let Some(text) = text {
+            &mut text.into_inner().sections[0].style.color
+        } else {
+            commands.entity(entity).remove::<FadeIn>();
+            continue;
+        };
+
+        let alpha = *fade.alpha.get_or_insert(color.a());
+        fade.elapsed += dt;
+        let progress = if fade.duration > 0.0 {
+            (fade.elapsed / fade.duration).min(1.0)
+        } else {
+            1.0
+        };
+        color.set_a(alpha * progress);
+        *visibility = Visibility::Inherited;
+
+        if progress >= 1.0 {
+            commands.entity(entity).remove::<FadeIn>();
+        }
+    }
+}
+
 fn cleanup_game_board(commands: &mut Commands, query: &Query<Entity, With<GameUI>>) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
@@ -928,9 +2022,25 @@ fn cleanup_game(
     }
 }
 
-fn render_final_board(parent: &mut ChildBuilder, game_state: &GameStateResource) {
-    let cell_size = 30.0;
-    let margin = 1.0;
+const THUMBNAIL_MARGIN: f32 = 1.0;
+
+/// Width and height of the board thumbnail, sized for the window.
+fn thumbnail_size(layout: &BoardLayout) -> (Val, Val) {
+    let step = layout.thumbnail_cell_size() + THUMBNAIL_MARGIN * 2.0;
+    (
+        Val::Px(layout.cols() as f32 * step),
+        Val::Px(layout.rows() as f32 * step),
+    )
+}
+
+fn render_final_board(
+    parent: &mut ChildBuilder,
+    game_state: &GameStateResource,
+    layout: &BoardLayout,
+    theme: &Theme,
+) {
+    let cell_size = layout.thumbnail_cell_size();
+    let (width, height) = thumbnail_size(layout);
     let rows = game_state.game.get_board().len();
     let cols = game_state.game.get_board()[0].len();
 
@@ -939,13 +2049,14 @@ fn render_final_board(parent: &mut ChildBuilder, game_state: &GameStateResource)
             NodeBundle {
                 style: Style {
                     flex_direction: FlexDirection::ColumnReverse,
-                    width: Val::Px((cols as f32) * (cell_size + margin * 2.0)),
-                    height: Val::Px((rows as f32) * (cell_size + margin * 2.0)),
+                    width,
+                    height,
                     ..default()
                 },
-                background_color: Color::rgb(0.1, 0.1, 0.3).into(),
+                background_color: theme.menu_background.into(),
                 ..default()
             },
+            FinalBoard,
             GameOverUI,
         ))
         .with_children(|board_parent| {
@@ -960,15 +2071,13 @@ fn render_final_board(parent: &mut ChildBuilder, game_state: &GameStateResource)
                     })
                     .with_children(|row_parent| {
                         for col in 0..cols {
-                            let cell_background = Color::rgba(0.9, 0.9, 0.9, 0.8);
+                            let cell_background = theme.cell.with_a(0.8);
 
                             let player = game_state.game.get_board()[row][col];
-                            let piece_color = if player == PLAYER_X {
-                                Some(Color::RED)
-                            } else if player == PLAYER_O {
-                                Some(Color::YELLOW)
+                            let piece_color = if player == PLAYER_X || player == PLAYER_O {
+                                Some(theme.player_color(player))
                             } else if player == OBSTACLE {
-                                Some(Color::BLACK)
+                                Some(theme.obstacle)
                             } else {
                                 None
                             };
@@ -979,12 +2088,13 @@ fn render_final_board(parent: &mut ChildBuilder, game_state: &GameStateResource)
                                         style: Style {
                                             width: Val::Px(cell_size),
                                             height: Val::Px(cell_size),
-                                            margin: UiRect::all(Val::Px(margin)),
+                                            margin: UiRect::all(Val::Px(THUMBNAIL_MARGIN)),
                                             ..default()
                                         },
                                         background_color: cell_background.into(),
                                         ..default()
                                     },
+                                    FinalBoardCell,
                                     GameOverUI,
                                 ))
                                 .with_children(|cell_parent| {
@@ -1013,6 +2123,8 @@ fn setup_game_over(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     game_state: Res<GameStateResource>,
+    layout: Res<BoardLayout>,
+    theme: Res<Theme>,
 ) {
     let message = if let Some(winner) = game_state.game.check_winner() {
         let player_number = if winner == PLAYER_X { "1" } else { "2" };
@@ -1032,7 +2144,7 @@ fn setup_game_over(
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                background_color: Color::rgb(0.1, 0.1, 0.3).into(),
+                background_color: theme.menu_background.into(),
                 ..default()
             },
             GameOverUI,
@@ -1042,9 +2154,9 @@ fn setup_game_over(
                 text: Text::from_section(
                     "Game Over",
                     TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font: theme.bold_font(&asset_server),
                         font_size: 70.0,
-                        color: Color::GOLD,
+                        color: theme.title,
                     },
                 )
                 .with_alignment(TextAlignment::Center),
@@ -1063,9 +2175,9 @@ fn setup_game_over(
                 text: Text::from_section(
                     message,
                     TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font: theme.bold_font(&asset_server),
                         font_size: 50.0,
-                        color: Color::WHITE,
+                        color: theme.text,
                     },
                 )
                 .with_alignment(TextAlignment::Center),
@@ -1080,7 +2192,7 @@ fn setup_game_over(
                 ..default()
             });
 
-            render_final_board(parent, &game_state);
+            render_final_board(parent, &game_state, &layout, &theme);
 
             parent.spawn(NodeBundle {
                 style: Style {
@@ -1101,7 +2213,7 @@ fn setup_game_over(
                             align_items: AlignItems::Center,
                             ..default()
                         },
-                        background_color: Color::rgb(0.15, 0.65, 0.15).into(),
+                        background_color: theme.button.into(),
                         ..default()
                     },
                     MainMenuButton,
@@ -1111,9 +2223,9 @@ fn setup_game_over(
                         text: Text::from_section(
                             "Main Menu",
                             TextStyle {
-                                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                                font: theme.bold_font(&asset_server),
                                 font_size: 40.0,
-                                color: Color::WHITE,
+                                color: theme.text,
                             },
                         )
                         .with_alignment(TextAlignment::Center),
@@ -1123,24 +2235,50 @@ fn setup_game_over(
         });
 }
 
+/// Keeps the board thumbnail fitted as the window is resized.
+fn fit_final_board(
+    layout: Res<BoardLayout>,
+    mut boards: Query<&mut Style, With<FinalBoard>>,
+    mut cells: Query<&mut Style, (With<FinalBoardCell>, Without<FinalBoard>)>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+    let (width, height) = thumbnail_size(&layout);
+    for mut board in &mut boards {
+        board.width = width;
+        board.height = height;
+    }
+    let cell_size = Val::Px(layout.thumbnail_cell_size());
+    for mut cell in &mut cells {
+        cell.width = cell_size;
+        cell.height = cell_size;
+    }
+}
+
 fn game_over_button_system(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<MainMenuButton>),
+        (Entity, Ref<Interaction>, &mut BackgroundColor),
+        With<MainMenuButton>,
     >,
+    focus: Res<gamepad::MenuFocus>,
     mut app_state: ResMut<NextState<AppState>>,
+    theme: Res<Theme>,
 ) {
-    for (interaction, mut background_color) in &mut interaction_query {
-        match *interaction {
+    for (button, interaction, mut background_color) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(button, interaction) else {
+            continue;
+        };
+        match interaction {
             Interaction::Pressed => {
-                *background_color = Color::rgb(0.10, 0.55, 0.10).into();
+                *background_color = theme.button_pressed.into();
                 app_state.set(AppState::MainMenu);
             }
             Interaction::Hovered => {
-                *background_color = Color::rgb(0.25, 0.75, 0.25).into();
+                *background_color = theme.button_hover.into();
             }
             Interaction::None => {
-                *background_color = Color::rgb(0.15, 0.65, 0.15).into();
+                *background_color = theme.button.into();
             }
         }
     }
@@ -1152,176 +2290,152 @@ fn cleanup_game_over(mut commands: Commands, query: Query<Entity, With<GameOverU
     }
 }
 
-fn get_board_dimensions(state: &GameStateResource) -> (f32, f32) {
-    let rows = state.game.get_board().len();
-    let cols = state.game.get_board()[0].len();
-
-    let cell_size = 75.0;
-    let padding = 7.5;
-    let board_width = cols as f32 * (cell_size + padding) - padding;
-    let board_height = rows as f32 * (cell_size + padding) - padding;
-
-    (board_width, board_height)
-}
-
-fn adjust_camera(
-    camera_query: &mut Query<(&mut OrthographicProjection, &mut Transform), With<MainCamera>>,
-    board_width: f32,
-    board_height: f32,
-) {
+/// Fits the board's view to the window at any aspect ratio, undoing any zoom
+/// and pan; the camera keeps it fitted as the window is resized.
+fn adjust_camera(camera_query: &mut MainCameraQuery, layout: &BoardLayout) {
+    let view = layout.view_size();
     for (mut ortho, mut transform) in camera_query.iter_mut() {
-        let desired_width = board_width + 200.0;
-        let desired_height = board_height + 200.0;
-
-        ortho.scaling_mode = ScalingMode::FixedVertical(desired_height);
-
-        ortho.area = Rect {
-            min: Vec2::new(-desired_width / 2.0, -desired_height / 2.0),
-            max: Vec2::new(desired_width / 2.0, desired_height / 2.0),
+        ortho.scaling_mode = ScalingMode::AutoMin {
+            min_width: view.x,
+            min_height: view.y,
         };
-
         transform.translation = Vec3::new(0.0, 0.0, transform.translation.z);
+        transform.scale = Vec3::ONE;
     }
 }
 
+/// Sets off the next power-up of a chain reaction once the last has had
+/// time to play.
+fn play_chain_reaction(
+    mut chain: ResMut<ChainReaction>,
+    mut events: EventWriter<PowerUpActivated>,
+    time: Res<Time>,
+    preferences: Res<config::Preferences>,
+) {
+    chain.wait = (chain.wait - time.delta_seconds() * preferences.animation_speed).max(0.0);
+    if chain.wait > 0.0 {
+        return;
+    }
+    if let Some(event) = chain.pending.pop_front() {
+        events.send(event);
+        chain.wait = CHAIN_STEP_TIME;
+    }
+}
+
+/// Run condition: every power-up the last move set off has been shown.
+fn chain_reaction_over(chain: Res<ChainReaction>) -> bool {
+    chain.pending.is_empty()
+}
+
 fn handle_power_up_activation(
     mut events: EventReader<PowerUpActivated>,
     mut commands: Commands,
-    query: Query<(Entity, &Cell), With<Cell>>,
     piece_query: Query<(Entity, &Piece), With<Piece>>,
     obstacle_query: Query<(Entity, &StaticObstacle), With<StaticObstacle>>,
     power_up_query: Query<(Entity, &PowerUpSymbol)>,
-    state: Res<GameStateResource>,
+    layout: Res<BoardLayout>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
+    mut sounds: EventWriter<PlaySound>,
 ) {
+    // What is drawn in each cell as the effects play out, with the piece's
+    // player or `OBSTACLE`, so later effects find what earlier ones spawned.
+    let mut cells: HashMap<(usize, usize), (Entity, char)> = piece_query
+        .iter()
+        .map(|(entity, piece)| ((piece.row, piece.col), (entity, piece.player)))
+        .chain(
+            obstacle_query
+                .iter()
+                .map(|(entity, obstacle)| ((obstacle.row, obstacle.col), (entity, OBSTACLE))),
+        )
+        .collect();
+
     for event in events.iter() {
+        // A power-up that did nothing, e.g. a targeted bomb with nothing in
+        // reach, goes quietly.
+        if !event.effects.is_empty() {
+            sounds.send(PlaySound(event.power_up.visual().cue.into()));
+        }
+
         for (entity, symbol) in power_up_query.iter() {
             if symbol.row == event.row && symbol.col == event.col {
                 commands.entity(entity).despawn_recursive();
             }
         }
 
-        for (entity, cell) in query.iter() {
-            if cell.row == event.row && cell.col == event.col {
-                match event.power_up {
-                    PowerUpType::Bomb => {
-                        let (board_width, board_height) = get_board_dimensions(&state);
-                        let cell_size = 75.0;
-                        let padding = 7.5;
-                        let board_offset_y = -50.0;
-
-                        commands.spawn((
-                            SpriteBundle {
-                                sprite: Sprite {
-                                    color: Color::PURPLE,
-                                    custom_size: Some(Vec2::new(50.0, 50.0)),
-                                    ..default()
-                                },
-                                transform: Transform::from_xyz(
-                                    cell.col as f32 * (cell_size + padding) - board_width / 2.0
-                                        + cell_size / 2.0,
-                                    cell.row as f32 * (cell_size + padding) - board_height / 2.0
-                                        + cell_size / 2.0
-                                        + board_offset_y,
-                                    3.0,
-                                ),
-                                ..default()
-                            },
-                            Explosion {
-                                timer: Timer::from_seconds(0.5, TimerMode::Once),
-                            },
-                            GameUI,
-                        ));
-
-                        if cell.row > 0 {
-                            let target_row = cell.row - 1;
-                            let target_col = cell.col;
-
-                            for (piece_entity, piece) in piece_query.iter() {
-                                if piece.row == target_row && piece.col == target_col {
-                                    commands.entity(piece_entity).despawn();
-                                    break;
-                                }
-                            }
-
-                            for (obstacle_entity, obstacle) in obstacle_query.iter() {
-                                if obstacle.row == target_row && obstacle.col == target_col {
-                                    commands.entity(obstacle_entity).despawn();
-                                    break;
-                                }
-                            }
-                        }
+        for effect in &event.effects {
+            match *effect {
+                Effect::Cleared { row, col } => {
+                    if let Some((entity, _)) = cells.remove(&(row, col)) {
+                        commands.entity(entity).despawn();
+                    }
+                }
+                Effect::ObstaclePlaced { row, col } => {
+                    let entity = spawn_obstacle(
+                        &mut commands,
+                        row,
+                        col,
+                        &layout,
+                        &mut Some(AnimatePiece::to(layout.row_y(row))),
+                        &theme,
+                        &skin,
+                    );
+                    cells.insert((row, col), (entity, OBSTACLE));
+                }
+                Effect::Converted { row, col, player } => {
+                    if let Some((entity, _)) = cells.remove(&(row, col)) {
+                        commands.entity(entity).despawn();
                     }
-                    PowerUpType::Skip => {
-                        commands.entity(entity).insert(Flashing);
+                    let piece = spawn_existing_piece(
+                        &mut commands,
+                        player,
+                        &layout,
+                        row,
+                        col,
+                        &mut meshes,
+                        &mut materials,
+                        &theme,
+                        &skin,
+                        false,
+                    );
+                    if let Some(entity) = piece {
+                        cells.insert((row, col), (entity, player));
                     }
-                    PowerUpType::Obstacle => {
-                        let (board_width, board_height) = get_board_dimensions(&state);
-                        let cell_size = 75.0;
-                        let padding = 7.5;
-                        let board_offset_y = -50.0;
-
-                        spawn_obstacle(
-                            &mut commands,
-                            cell.row,
-                            cell.col,
-                            cell_size,
-                            padding,
-                            board_width,
-                            board_height,
-                            board_offset_y,
-                            &mut Some(AnimatePiece {
-                                target_y: cell.row as f32 * (cell_size + padding)
-                                    - board_height / 2.0
-                                    + cell_size / 2.0
-                                    + board_offset_y,
-                            }),
-                        );
+                }
+                Effect::Fell {
+                    col,
+                    from_row,
+                    to_row,
+                } => {
+                    let Some((entity, cell)) = cells.remove(&(from_row, col)) else {
+                        continue;
+                    };
+                    // Slides down from where it is drawn now.
+                    let mut entity_commands = commands.entity(entity);
+                    entity_commands.insert(AnimatePiece::to(layout.row_y(to_row)));
+                    if cell == OBSTACLE {
+                        entity_commands.insert(StaticObstacle { row: to_row, col });
+                    } else {
+                        entity_commands.insert(Piece {
+                            player: cell,
+                            row: to_row,
+                            col,
+                        });
                     }
+                    cells.insert((to_row, col), (entity, cell));
                 }
+                // The turn skip itself is shown by `particles`.
+                Effect::SkipTurns(_) => {}
             }
         }
     }
 }
 
-fn explosion_animation(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Explosion, &mut Sprite)>,
-) {
-    for (entity, mut explosion, mut sprite) in query.iter_mut() {
-        explosion.timer.tick(time.delta());
-
-        let scale_factor = 1.0 + (0.5 * explosion.timer.elapsed_secs());
-        sprite.custom_size = Some(Vec2::splat(50.0 * scale_factor));
-
-        if explosion.timer.finished() {
-            commands.entity(entity).despawn();
-        }
-    }
-}
-
-fn flash_power_up(
-    mut query: Query<(Entity, &mut Sprite), With<Flashing>>,
-    time: Res<Time>,
-    mut timer: Local<f32>,
-    mut commands: Commands,
-) {
-    *timer += time.delta_seconds();
-    if *timer > 0.5 {
-        for (entity, mut sprite) in query.iter_mut() {
-            sprite.color = if sprite.color.a() == 1.0 {
-                Color::WHITE
-            } else {
-                Color::GREEN
-            };
-            commands.entity(entity).remove::<Flashing>();
-        }
-        *timer = 0.0;
-    }
-}
-
 fn synchronize_frontend(
     state: Res<GameStateResource>,
+    layout: Res<BoardLayout>,
     mut commands: Commands,
     existing_pieces: Query<(Entity, &Piece), With<Piece>>,
     existing_obstacles: Query<(Entity, &StaticObstacle), With<StaticObstacle>>,
@@ -1329,7 +2443,16 @@ fn synchronize_frontend(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
+    chain: Res<ChainReaction>,
 ) {
+    // Wait for the view to grow into an expanded board, and for a chain
+    // reaction to play out, first.
+    if !layout.fits(&state.game) || !chain.pending.is_empty() {
+        return;
+    }
+
     let board = state.game.get_board();
 
     let mut required_piece_positions = HashSet::new();
@@ -1365,38 +2488,27 @@ fn synchronize_frontend(
     for &(row, col) in &required_piece_positions {
         spawn_existing_piece(
             &mut commands,
-            &state.game,
+            board[row][col],
+            &layout,
             row,
             col,
             &mut meshes,
             &mut materials,
+            &theme,
+            &skin,
+            true,
         );
     }
 
     for &(row, col) in &required_obstacle_positions {
-        let cell_size = 75.0;
-        let padding = 7.5;
-        let (board_width, board_height) = get_board_dimensions(&state);
-        let board_offset_y = -50.0;
-
-        spawn_obstacle(
-            &mut commands,
-            row,
-            col,
-            cell_size,
-            padding,
-            board_width,
-            board_height,
-            board_offset_y,
-            &mut None,
-        );
+        spawn_obstacle(&mut commands, row, col, &layout, &mut None, &theme, &skin);
     }
 
     let mut required_power_up_positions = HashSet::new();
     for (row, row_cells) in board.iter().enumerate() {
         for (col, &cell) in row_cells.iter().enumerate() {
-            if let Some(pu) = PowerUpType::from_char(cell) {
-                required_power_up_positions.insert((row, col, pu));
+            if power_ups::is_power_up(cell) {
+                required_power_up_positions.insert((row, col, cell));
             }
         }
     }
@@ -1415,72 +2527,238 @@ fn synchronize_frontend(
         .map(|(_, symbol)| (symbol.row, symbol.col))
         .collect();
 
-    for &(row, col, pu) in &required_power_up_positions {
+    for &(row, col, glyph) in &required_power_up_positions {
+        let Some(pu) = power_ups::registry().get(glyph) else {
+            continue;
+        };
         if !existing_positions.contains(&(row, col)) {
-            let cell_size = 75.0;
-            let padding = 7.5;
-            let cols = board[0].len();
-            let rows = board.len();
-            let board_width = cols as f32 * (cell_size + padding) - padding;
-            let board_height = rows as f32 * (cell_size + padding) - padding;
-            let board_offset_y = -50.0;
-
             spawn_power_up_symbol(
                 &mut commands,
                 row,
                 col,
                 pu,
-                cell_size,
-                padding,
-                board_width,
-                board_height,
-                board_offset_y,
+                &layout,
                 &asset_server,
+                &theme,
+                &skin,
             );
         }
     }
 }
 
 pub fn run() {
+    let (config, config_path, config_problem) = config::ConfigFile::load();
+    let window = config.preferences.window.clone();
+    let layout = BoardLayout::new(&GameStateResource::default().game, window.size());
+    let themes = theme::Themes::load();
+    let theme = themes.get(&config.preferences.theme);
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Rusty Connect Four".to_string(),
-                resolution: (1280.0, 720.0).into(),
-                resizable: true,
-                ..default()
+                ..window.to_window()
             }),
             ..default()
         }))
-        .insert_resource(ClearColor(Color::rgb(0.05, 0.05, 0.2)))
+        .insert_resource(ClearColor(theme.background))
+        .insert_resource(theme)
+        .insert_resource(themes)
+        .insert_resource(skin::Skins::load())
+        .init_resource::<LoadedSkin>()
         .insert_resource(GameStateResource::default())
+        .insert_resource(layout)
+        .insert_resource(UiScale {
+            scale: layout.ui_scale() as f64,
+        })
+        .init_resource::<ColumnCursor>()
+        .init_resource::<ChainReaction>()
+        .init_resource::<ItemSelection>()
+        .init_resource::<PauseReason>()
+        .insert_resource(config.controls)
+        .insert_resource(config.settings)
+        .insert_resource(config.preferences)
+        .insert_resource(config_path)
+        .insert_resource(config_problem)
+        .init_resource::<SettingsDraft>()
+        .init_resource::<controls::Rebinding>()
+        .init_resource::<gamepad::GamepadSeats>()
+        .init_resource::<gamepad::MenuFocus>()
         .add_event::<PowerUpActivated>()
+        .add_event::<DropRequest>()
+        .add_event::<TargetRequest>()
+        .add_event::<PowerUpSpawned>()
+        .add_event::<PieceLanded>()
+        .add_event::<PlaySound>()
+        .add_event::<ParticleBurst>()
+        .init_resource::<ParticlePool>()
+        .add_audio_source::<audio::Synth>()
+        .init_resource::<audio::Sounds>()
         .add_state::<AppState>()
-        .add_systems(Startup, setup)
+        .add_state::<PauseState>()
+        .add_systems(
+            Startup,
+            (
+                setup,
+                config::report_config_problem,
+                particles::setup_particle_pool,
+            ),
+        )
+        // Before state transitions, so a screen entered right after the skin
+        // changes or the window resizes is already drawn to match.
+        .add_systems(PreUpdate, (skin::apply_skin, layout::fit_to_window))
+        // Before the button systems, which read the focus alongside the mouse.
+        .add_systems(
+            PreUpdate,
+            gamepad::gamepad_menu_navigation
+                .after(InputSystem)
+                .after(UiSystem::Focus),
+        )
+        .add_systems(Update, gamepad::assign_gamepad_seats)
         .add_systems(OnEnter(AppState::MainMenu), setup_main_menu)
         .add_systems(
             Update,
-            (main_menu_button_system, power_ups_toggle_system).run_if(in_state(AppState::MainMenu)),
+            (
+                fade_toasts,
+                config::persist_config,
+                theme::apply_theme,
+                audio::play_button_clicks,
+                audio::play_sounds,
+                audio::play_landing_sounds,
+                audio::update_music,
+                display::apply_window_preferences,
+                display::remember_window_placement,
+                display::toggle_fullscreen.run_if(not(in_state(AppState::Controls))),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                main_menu_button_system,
+                continue_button_system,
+                settings_button_system,
+            )
+                .run_if(in_state(AppState::MainMenu)),
         )
         .add_systems(OnExit(AppState::MainMenu), cleanup_main_menu)
+        .add_systems(OnEnter(AppState::Settings), settings::setup_settings_screen)
+        .add_systems(
+            Update,
+            (
+                settings::setting_button_system,
+                settings::update_setting_labels,
+                settings::settings_menu_button_system,
+            )
+                .run_if(in_state(AppState::Settings)),
+        )
+        .add_systems(
+            OnExit(AppState::Settings),
+            settings::cleanup_settings_screen,
+        )
+        .add_systems(OnEnter(AppState::Controls), controls::setup_controls_screen)
+        .add_systems(
+            Update,
+            (
+                controls::binding_button_system,
+                controls::capture_rebinding_key,
+                controls::update_binding_labels,
+                controls::controls_menu_button_system,
+            )
+                .run_if(in_state(AppState::Controls)),
+        )
+        .add_systems(
+            OnExit(AppState::Controls),
+            controls::cleanup_controls_screen,
+        )
         .add_systems(OnEnter(AppState::InGame), setup_game)
+        .add_systems(
+            Update,
+            (
+                (
+                    keyboard_column_input,
+                    keyboard_cursor_input,
+                    mouse_column_input,
+                    gamepad::gamepad_column_input,
+                    inventory::item_input,
+                )
+                    .run_if(human_turn),
+                computer_turn,
+                undo_move,
+                quick_save,
+                pause_game,
+            )
+                .before(update_game)
+                .run_if(game_in_progress)
+                .run_if(chain_reaction_over)
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(PauseState::Running)),
+        )
         .add_systems(
             Update,
             (
                 update_game,
+                update_ghost_piece,
+                update_column_selector,
+                inventory::update_inventory_panel,
+                inventory::update_aim_marker,
+                highlight_column_label,
                 animate_pieces,
-                handle_power_up_activation,
-                explosion_animation,
-                flash_power_up,
+                expand_board_view,
+                pop_in_power_ups,
+                animate_tweens,
+                animate_fade_ins,
+                (play_chain_reaction, handle_power_up_activation)
+                    .chain()
+                    .after(update_game)
+                    .before(synchronize_frontend),
                 synchronize_frontend,
+                announce_spawned_power_ups.after(update_game),
+                finish_won_game,
+            )
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(
+            Update,
+            (
+                particles::power_up_particles,
+                particles::landing_dust,
+                particles::emit_particles,
+                particles::update_particles,
+            )
+                .chain()
+                .after(play_chain_reaction)
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(
+            Update,
+            (
+                camera::zoom_camera,
+                camera::pan_camera,
+                camera::follow_column_cursor,
+                camera::fit_board,
             )
-                .run_if(in_state(AppState::InGame)),
+                .chain()
+                .after(mouse_column_input)
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(
+            OnExit(AppState::InGame),
+            (cleanup_game, particles::clear_particles),
+        )
+        .add_systems(OnEnter(PauseState::Paused), pause::setup_pause_menu)
+        .add_systems(
+            Update,
+            (pause::resume_game, pause::pause_menu_button_system)
+                .run_if(in_state(PauseState::Paused)),
         )
-        .add_systems(OnExit(AppState::InGame), cleanup_game)
+        .add_systems(OnExit(PauseState::Paused), pause::cleanup_pause_menu)
         .add_systems(OnEnter(AppState::GameOver), setup_game_over)
         .add_systems(
             Update,
-            game_over_button_system.run_if(in_state(AppState::GameOver)),
+            (game_over_button_system, fit_final_board).run_if(in_state(AppState::GameOver)),
         )
         .add_systems(OnExit(AppState::GameOver), cleanup_game_over)
         .run();
@@ -0,0 +1,371 @@
+use super::config::Preferences;
+use super::gamepad::MenuFocus;
+use super::PieceLanded;
+use crate::power_ups::Cue;
+use bevy::audio::{AudioSink, AudioSinkPlayback, Decodable, Source, Volume};
+use bevy::prelude::*;
+use bevy::reflect::{TypePath, TypeUuid};
+use bevy::utils::HashSet;
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Length of each note of the background music, in seconds.
+const MUSIC_BEAT: f32 = 0.3;
+/// Chords the background music arpeggiates, in Hz.
+const MUSIC_CHORDS: [[f32; 3]; 4] = [
+    [261.63, 329.63, 392.00], // C
+    [220.00, 261.63, 329.63], // Am
+    [174.61, 220.00, 261.63], // F
+    [196.00, 246.94, 293.66], // G
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum SoundEffect {
+    Drop,
+    Land,
+    Invalid,
+    Bomb,
+    Skip,
+    Obstacle,
+    Expand,
+    Win,
+    Tie,
+    Click,
+}
+
+impl SoundEffect {
+    const ALL: [SoundEffect; 10] = [
+        SoundEffect::Drop,
+        SoundEffect::Land,
+        SoundEffect::Invalid,
+        SoundEffect::Bomb,
+        SoundEffect::Skip,
+        SoundEffect::Obstacle,
+        SoundEffect::Expand,
+        SoundEffect::Win,
+        SoundEffect::Tie,
+        SoundEffect::Click,
+    ];
+
+    fn synthesize(&self) -> Vec<f32> {
+        match self {
+            SoundEffect::Drop => sweep(700.0, 350.0, 0.08, sine, 0.3),
+            SoundEffect::Land => mix(sweep(180.0, 60.0, 0.15, sine, 0.8), noise(0.03, 0.2, 0.5)),
+            SoundEffect::Invalid => sequence(&[
+                sweep(140.0, 140.0, 0.1, square, 0.25),
+                silence(0.04),
+                sweep(110.0, 110.0, 0.14, square, 0.25),
+            ]),
+            SoundEffect::Bomb => mix(noise(0.6, 0.9, 0.08), sweep(120.0, 30.0, 0.5, sine, 0.8)),
+            SoundEffect::Skip => sweep(400.0, 1200.0, 0.2, triangle, 0.5),
+            SoundEffect::Obstacle => {
+                mix(sweep(90.0, 40.0, 0.3, square, 0.4), noise(0.15, 0.6, 0.3))
+            }
+            SoundEffect::Expand => sweep(200.0, 800.0, 0.5, triangle, 0.5),
+            SoundEffect::Win => notes(&[523.25, 659.25, 783.99, 1046.50], 0.12, triangle),
+            SoundEffect::Tie => notes(&[392.00, 329.63, 261.63], 0.18, triangle),
+            SoundEffect::Click => sweep(1000.0, 1000.0, 0.03, sine, 0.3),
+        }
+    }
+}
+
+impl From<Cue> for SoundEffect {
+    fn from(cue: Cue) -> Self {
+        match cue {
+            Cue::Explosion => SoundEffect::Bomb,
+            Cue::Shockwave => SoundEffect::Skip,
+            Cue::Rumble => SoundEffect::Obstacle,
+        }
+    }
+}
+
+/// Asks for a sound effect to be played this frame.
+#[derive(Event)]
+pub(super) struct PlaySound(pub(super) SoundEffect);
+
+/// Mono audio synthesized at startup, so the game needs no sound files.
+#[derive(Debug, Clone, TypeUuid, TypePath)]
+#[uuid = "5b1f1c8e-2f6a-4d43-9a0e-8e3c0d6f4a21"]
+pub(super) struct Synth {
+    samples: Arc<[f32]>,
+}
+
+pub(super) struct SynthDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+impl From<Vec<f32>> for Synth {
+    fn from(samples: Vec<f32>) -> Self {
+        Self {
+            samples: samples.into(),
+        }
+    }
+}
+
+fn sine(phase: f32) -> f32 {
+    (phase * TAU).sin()
+}
+
+fn square(phase: f32) -> f32 {
+    if phase.fract() < 0.5 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+fn triangle(phase: f32) -> f32 {
+    4.0 * (phase.fract() - 0.5).abs() - 1.0
+}
+
+/// Fades in quickly and out along a curve over `duration` seconds.
+fn envelope(t: f32, duration: f32) -> f32 {
+    let attack = (t / 0.005).min(1.0);
+    attack * (1.0 - t / duration).max(0.0).powi(2)
+}
+
+/// `duration` seconds of `wave` gliding from `from` to `to` Hz.
+fn sweep(from: f32, to: f32, duration: f32, wave: fn(f32) -> f32, amplitude: f32) -> Vec<f32> {
+    let count = (duration * SAMPLE_RATE as f32) as usize;
+    let mut phase = 0.0;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            phase += (from + (to - from) * t / duration) / SAMPLE_RATE as f32;
+            wave(phase) * envelope(t, duration) * amplitude
+        })
+        .collect()
+}
+
+/// A burst of noise; lower `brightness` muffles it.
+fn noise(duration: f32, amplitude: f32, brightness: f32) -> Vec<f32> {
+    let count = (duration * SAMPLE_RATE as f32) as usize;
+    let mut filtered = 0.0;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            filtered += brightness * (rand::random::<f32>() * 2.0 - 1.0 - filtered);
+            filtered * envelope(t, duration) * amplitude
+        })
+        .collect()
+}
+
+fn silence(duration: f32) -> Vec<f32> {
+    vec![0.0; (duration * SAMPLE_RATE as f32) as usize]
+}
+
+/// Plays `a` and `b` together.
+fn mix(a: Vec<f32>, b: Vec<f32>) -> Vec<f32> {
+    let (mut long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    for (sample, other) in long.iter_mut().zip(short) {
+        *sample += other;
+    }
+    long
+}
+
+/// Plays the parts one after another.
+fn sequence(parts: &[Vec<f32>]) -> Vec<f32> {
+    parts.concat()
+}
+
+fn notes(frequencies: &[f32], length: f32, wave: fn(f32) -> f32) -> Vec<f32> {
+    let parts: Vec<Vec<f32>> = frequencies
+        .iter()
+        .map(|&frequency| sweep(frequency, frequency, length, wave, 0.4))
+        .collect();
+    sequence(&parts)
+}
+
+/// A calm arpeggio over four chords that loops seamlessly.
+fn synthesize_music() -> Vec<f32> {
+    let parts: Vec<Vec<f32>> = MUSIC_CHORDS
+        .iter()
+        .flat_map(|chord| {
+            [0, 1, 2, 1, 0, 1, 2, 1].map(|note| {
+                mix(
+                    sweep(chord[note], chord[note], MUSIC_BEAT, triangle, 0.12),
+                    sweep(chord[0] / 2.0, chord[0] / 2.0, MUSIC_BEAT, sine, 0.08),
+                )
+            })
+        })
+        .collect();
+    sequence(&parts)
+}
+
+/// Handles to every synthesized sound.
+#[derive(Resource)]
+pub(super) struct Sounds {
+    effects: HashMap<SoundEffect, Handle<Synth>>,
+    music: Handle<Synth>,
+}
+
+impl Sounds {
+    fn play(&self, commands: &mut Commands, effect: SoundEffect, level: f32) {
+        if level <= 0.0 {
+            return;
+        }
+        commands.spawn(AudioSourceBundle {
+            source: self.effects[&effect].clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_absolute(level)),
+        });
+    }
+}
+
+impl FromWorld for Sounds {
+    fn from_world(world: &mut World) -> Self {
+        let mut synths = world.resource_mut::<Assets<Synth>>();
+        Self {
+            effects: SoundEffect::ALL
+                .into_iter()
+                .map(|effect| (effect, synths.add(effect.synthesize().into())))
+                .collect(),
+            music: synths.add(synthesize_music().into()),
+        }
+    }
+}
+
+/// Marks the entity playing the background music.
+#[derive(Component)]
+pub(super) struct Music;
+
+fn effects_level(preferences: &Preferences) -> f32 {
+    if preferences.muted {
+        0.0
+    } else {
+        preferences.volume * preferences.effects_volume
+    }
+}
+
+fn music_level(preferences: &Preferences) -> f32 {
+    if preferences.muted || !preferences.music {
+        0.0
+    } else {
+        preferences.volume * preferences.music_volume
+    }
+}
+
+pub(super) fn play_sounds(
+    mut commands: Commands,
+    mut events: EventReader<PlaySound>,
+    sounds: Res<Sounds>,
+    preferences: Res<Preferences>,
+) {
+    let level = effects_level(&preferences);
+    // The same sound several times in one frame is only played once.
+    let mut played = HashSet::new();
+    for PlaySound(effect) in events.iter() {
+        if played.insert(*effect) {
+            sounds.play(&mut commands, *effect, level);
+        }
+    }
+}
+
+/// Lands louder the harder a piece hits. Pieces landing together, e.g. after
+/// an expansion, sound as one.
+pub(super) fn play_landing_sounds(
+    mut commands: Commands,
+    mut landings: EventReader<PieceLanded>,
+    sounds: Res<Sounds>,
+    preferences: Res<Preferences>,
+) {
+    if let Some(impact) = landings
+        .iter()
+        .map(|landing| landing.impact)
+        .reduce(f32::max)
+    {
+        let level = effects_level(&preferences) * impact.max(0.3);
+        sounds.play(&mut commands, SoundEffect::Land, level);
+    }
+}
+
+/// Clicks for every button press, on any screen.
+pub(super) fn play_button_clicks(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    focus: Res<MenuFocus>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    if focus.activated()
+        || interaction_query
+            .iter()
+            .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        sounds.send(PlaySound(SoundEffect::Click));
+    }
+}
+
+/// Starts, stops or adjusts the music whenever the preferences change.
+pub(super) fn update_music(
+    mut commands: Commands,
+    preferences: Res<Preferences>,
+    sounds: Res<Sounds>,
+    music_query: Query<(Entity, Option<&AudioSink>), With<Music>>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+
+    let level = music_level(&preferences);
+    match music_query.get_single() {
+        Ok((entity, _)) if level <= 0.0 => commands.entity(entity).despawn(),
+        Ok((_, Some(sink))) => sink.set_volume(level),
+        // Still starting up; it will pick up the next change.
+        Ok((_, None)) => {}
+        Err(_) if level > 0.0 => {
+            commands.spawn((
+                AudioSourceBundle {
+                    source: sounds.music.clone(),
+                    settings: PlaybackSettings::LOOP.with_volume(Volume::new_absolute(level)),
+                },
+                Music,
+            ));
+        }
+        Err(_) => {}
+    }
+}
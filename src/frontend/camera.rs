@@ -0,0 +1,217 @@
+use super::controls::{Action, InputMap};
+use super::layout::BoardLayout;
+use super::{adjust_camera, ColumnCursor, MainCamera, MainCameraQuery, Tween};
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::input::touchpad::TouchpadMagnify;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Closest zoom, as a share of the fitted view.
+const MIN_SCALE: f32 = 0.25;
+/// Zoom change per notch of the mouse wheel.
+const WHEEL_ZOOM: f32 = 0.1;
+/// Zoom change per second while a zoom key is held, as a rate of growth.
+const KEY_ZOOM_SPEED: f32 = 1.5;
+/// Fitted views per second the pan keys move the camera.
+const KEY_PAN_SPEED: f32 = 0.8;
+/// How quickly the camera catches up with the column cursor, per second.
+const FOLLOW_RATE: f32 = 10.0;
+
+/// The main camera while the player controls it; an expansion's zoom out
+/// takes over until it has finished.
+type ControlledCamera<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static OrthographicProjection),
+    (With<MainCamera>, Without<Tween>),
+>;
+
+/// Keeps as much of the view as possible on the board and its labels.
+fn clamp_to_board(
+    transform: &mut Transform,
+    projection: &OrthographicProjection,
+    layout: &BoardLayout,
+) {
+    let visible = projection.area.size() * transform.scale.truncate();
+    let room = ((layout.view_size() - visible) / 2.0).max(Vec2::ZERO);
+    let position = transform.translation.truncate().clamp(-room, room);
+    transform.translation = position.extend(transform.translation.z);
+}
+
+/// Scales the view by `factor`, keeping the world point under `anchor`
+/// (relative to the window centre, in window pixels) where it is.
+fn zoom_by(
+    transform: &mut Transform,
+    projection: &OrthographicProjection,
+    window: &Window,
+    factor: f32,
+    anchor: Vec2,
+) {
+    let old_scale = transform.scale.x;
+    let scale = (old_scale * factor).clamp(MIN_SCALE, 1.0);
+    let units_per_pixel = projection.area.width() / window.width();
+    transform.translation += (anchor * units_per_pixel * (old_scale - scale)).extend(0.0);
+    transform.scale = Vec3::new(scale, scale, 1.0);
+}
+
+/// Mouse position relative to the window centre, with y pointing up.
+fn cursor_offset(window: &Window) -> Vec2 {
+    window.cursor_position().map_or(Vec2::ZERO, |cursor| {
+        let offset = cursor - Vec2::new(window.width(), window.height()) / 2.0;
+        Vec2::new(offset.x, -offset.y)
+    })
+}
+
+/// Zooms with the mouse wheel or a touchpad pinch towards the pointer, and
+/// with the zoom keys towards the centre.
+pub(super) fn zoom_camera(
+    mut wheel: EventReader<MouseWheel>,
+    mut magnify: EventReader<TouchpadMagnify>,
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: ControlledCamera,
+    layout: Res<BoardLayout>,
+    time: Res<Time>,
+) {
+    let notches: f32 = wheel
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            // Touchpads scroll in pixels; about a line's worth per notch.
+            MouseScrollUnit::Pixel => event.y / 20.0,
+        })
+        .sum();
+    let pinch: f32 = magnify.iter().map(|event| event.0).sum();
+
+    let mut key_zoom = 0.0;
+    if input_map.pressed(Action::ZoomIn, &keyboard_input) {
+        key_zoom -= KEY_ZOOM_SPEED * time.delta_seconds();
+    }
+    if input_map.pressed(Action::ZoomOut, &keyboard_input) {
+        key_zoom += KEY_ZOOM_SPEED * time.delta_seconds();
+    }
+
+    if notches == 0.0 && pinch == 0.0 && key_zoom == 0.0 {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((mut transform, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let pointer = cursor_offset(window);
+    let pointer_zoom = (1.0 - WHEEL_ZOOM).powf(notches) * (1.0 - pinch).max(0.1);
+    zoom_by(&mut transform, projection, window, pointer_zoom, pointer);
+    zoom_by(
+        &mut transform,
+        projection,
+        window,
+        key_zoom.exp(),
+        Vec2::ZERO,
+    );
+    clamp_to_board(&mut transform, projection, &layout);
+}
+
+/// Pans by dragging with the right mouse button or with the pan keys.
+pub(super) fn pan_camera(
+    mut motion: EventReader<MouseMotion>,
+    mouse_input: Res<Input<MouseButton>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: ControlledCamera,
+    layout: Res<BoardLayout>,
+    time: Res<Time>,
+) {
+    let dragged: Vec2 = motion.iter().map(|event| event.delta).sum();
+    let dragging = mouse_input.pressed(MouseButton::Right) && dragged != Vec2::ZERO;
+
+    let mut direction = Vec2::ZERO;
+    for (action, step) in [
+        (Action::PanLeft, Vec2::NEG_X),
+        (Action::PanRight, Vec2::X),
+        (Action::PanUp, Vec2::Y),
+        (Action::PanDown, Vec2::NEG_Y),
+    ] {
+        if input_map.pressed(action, &keyboard_input) {
+            direction += step;
+        }
+    }
+
+    if !dragging && direction == Vec2::ZERO {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((mut transform, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let scale = transform.scale.x;
+    let mut shift = direction * KEY_PAN_SPEED * layout.view_size() * scale * time.delta_seconds();
+    if dragging {
+        // The board follows the pointer, so the camera moves the other way.
+        let units_per_pixel = projection.area.width() / window.width() * scale;
+        shift += Vec2::new(-dragged.x, dragged.y) * units_per_pixel;
+    }
+    transform.translation += shift.extend(0.0);
+    clamp_to_board(&mut transform, projection, &layout);
+}
+
+/// Brings the column cursor back into view when zoomed in.
+pub(super) fn follow_column_cursor(
+    cursor: Res<ColumnCursor>,
+    mut camera_query: ControlledCamera,
+    layout: Res<BoardLayout>,
+    time: Res<Time>,
+    mut target: Local<Option<f32>>,
+) {
+    let Ok((mut transform, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    if cursor.is_changed() {
+        if let Some(col) = cursor.col {
+            let half_width = projection.area.width() * transform.scale.x / 2.0;
+            let reach = (half_width - layout.cell_size()).max(0.0);
+            let x = layout.column_x(col);
+            let camera_x = transform.translation.x;
+            if x < camera_x - reach {
+                *target = Some(x + reach);
+            } else if x > camera_x + reach {
+                *target = Some(x - reach);
+            }
+        }
+    }
+
+    let Some(target_x) = *target else {
+        return;
+    };
+    let catch_up = 1.0 - (-FOLLOW_RATE * time.delta_seconds()).exp();
+    let before = transform.translation.x;
+    transform.translation.x += (target_x - before) * catch_up;
+    clamp_to_board(&mut transform, projection, &layout);
+
+    // Stop once there, or when held back by the edge of the board.
+    let arrived = (target_x - transform.translation.x).abs() < 0.5;
+    let stuck = (transform.translation.x - before).abs() < 0.01;
+    if arrived || stuck {
+        *target = None;
+    }
+}
+
+/// Zooms back out to show the whole board.
+pub(super) fn fit_board(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut camera_query: MainCameraQuery,
+    layout: Res<BoardLayout>,
+) {
+    if input_map.just_pressed(Action::FitBoard, &keyboard_input) {
+        adjust_camera(&mut camera_query, &layout);
+    }
+}
@@ -0,0 +1,219 @@
+use super::controls::InputMap;
+use super::display::WindowConfig;
+use super::settings::Settings;
+use super::theme::Theme;
+use super::{spawn_toast, storage};
+use crate::mods;
+use crate::power_ups::{self, Registry};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_FILE: &str = "config.ron";
+/// Folder in the config directory searched for `.ron` power-up definitions.
+const POWER_UPS_DIR: &str = "power_ups";
+/// Folder in the config directory searched for `.rhai` mod scripts.
+const MODS_DIR: &str = "mods";
+
+/// Presentation options that aren't part of the game rules.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct Preferences {
+    pub(super) window: WindowConfig,
+    pub(super) theme: String,
+    pub(super) skin: String,
+    /// Multiplier for how fast pieces fall.
+    pub(super) animation_speed: f32,
+    /// Pieces fall steadily, without bouncing or squashing.
+    pub(super) reduced_motion: bool,
+    /// Master volume from 0.0 to 1.0.
+    pub(super) volume: f32,
+    /// Sound effect volume from 0.0 to 1.0, scaled by `volume`.
+    pub(super) effects_volume: f32,
+    /// Whether background music plays.
+    pub(super) music: bool,
+    /// Music volume from 0.0 to 1.0, scaled by `volume`.
+    pub(super) music_volume: f32,
+    /// Silences everything without losing the volume levels.
+    pub(super) muted: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            window: WindowConfig::default(),
+            theme: "classic".to_string(),
+            skin: "flat".to_string(),
+            animation_speed: 1.0,
+            reduced_motion: false,
+            volume: 0.8,
+            effects_volume: 1.0,
+            music: true,
+            music_volume: 0.5,
+            muted: false,
+        }
+    }
+}
+
+/// Everything remembered between launches except the saved game.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct ConfigFile {
+    pub(super) settings: Settings,
+    pub(super) controls: InputMap,
+    pub(super) preferences: Preferences,
+}
+
+/// Where the config file is read from and written back to.
+#[derive(Resource)]
+pub(super) struct ConfigPath(PathBuf);
+
+/// Problem found while loading the config, shown once the window is up.
+#[derive(Resource, Default)]
+pub(super) struct ConfigProblem(Option<String>);
+
+/// The `--config <path>` (or `--config=<path>`) argument, if given.
+fn path_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Puts the built-in power-ups, any definition files and any mods into play.
+/// Files that can't be used are reported and skipped.
+fn load_power_ups() -> Option<String> {
+    let (mut registry, problems) = Registry::load(&storage::config_path(POWER_UPS_DIR));
+    let mod_problems = mods::load(&storage::config_path(MODS_DIR), &mut registry);
+    power_ups::install(registry);
+
+    let problems: Vec<_> = problems
+        .iter()
+        .map(|problem| format!("power-up {}", problem))
+        .chain(
+            mod_problems
+                .iter()
+                .map(|problem| format!("mod {}", problem)),
+        )
+        .collect();
+    for problem in &problems {
+        eprintln!("Could not load {}", problem);
+    }
+    let first = problems.first()?;
+    Some(match problems.len() {
+        1 => format!("Skipped {}", first),
+        count => format!("Skipped {} ({} more in the log)", first, count - 1),
+    })
+}
+
+impl ConfigFile {
+    /// Loads the config from `--config` or the platform config directory.
+    /// A missing file is created with the defaults; a malformed one is moved
+    /// aside to `.bak` and replaced by the defaults.
+    pub(super) fn load() -> (Self, ConfigPath, ConfigProblem) {
+        let path = path_from_args().unwrap_or_else(|| storage::config_path(CONFIG_FILE));
+        // The game settings are checked against the power-ups in play.
+        let power_up_problem = load_power_ups();
+
+        let (mut config, mut problem) = match storage::read_ron::<ConfigFile>(&path) {
+            Ok(Some(config)) => (config, None),
+            Ok(None) => (Self::default(), None),
+            Err(err) => {
+                let backup = path.with_extension("ron.bak");
+                eprintln!(
+                    "Could not load config, replacing it with defaults (old file kept as {}): {}",
+                    backup.display(),
+                    err
+                );
+                if let Err(err) = fs::rename(&path, &backup) {
+                    eprintln!("Could not back up {}: {}", path.display(), err);
+                }
+                (
+                    Self::default(),
+                    Some("The config file was damaged and has been reset.".to_string()),
+                )
+            }
+        };
+
+        // Power-ups whose definitions are gone can't be enabled.
+        config
+            .settings
+            .game
+            .enabled_power_ups
+            .retain(|&glyph| power_ups::is_power_up(glyph));
+        if let Err(err) = config.settings.game.validate() {
+            eprintln!(
+                "Configured game settings are invalid, using defaults: {}",
+                err
+            );
+            config.settings = Settings::default();
+            problem.get_or_insert_with(|| "Invalid game settings were reset.".to_string());
+        }
+        problem = problem.or(power_up_problem);
+        config.controls.fill_missing();
+        let preferences = &mut config.preferences;
+        preferences.window.validate();
+        if preferences.animation_speed.is_nan() || preferences.animation_speed <= 0.0 {
+            preferences.animation_speed = 1.0;
+        }
+        for volume in [
+            &mut preferences.volume,
+            &mut preferences.effects_volume,
+            &mut preferences.music_volume,
+        ] {
+            *volume = volume.clamp(0.0, 1.0);
+        }
+
+        let path = ConfigPath(path);
+        config.save(&path);
+        (config, path, ConfigProblem(problem))
+    }
+
+    fn save(&self, path: &ConfigPath) {
+        if let Err(err) = storage::write_ron(&path.0, self) {
+            eprintln!("Could not save config: {}", err);
+        }
+    }
+}
+
+/// Writes the config back whenever the settings, key bindings or preferences
+/// change.
+pub(super) fn persist_config(
+    path: Res<ConfigPath>,
+    settings: Res<Settings>,
+    input_map: Res<InputMap>,
+    preferences: Res<Preferences>,
+) {
+    // Everything was just loaded (and written) on startup.
+    if settings.is_added() {
+        return;
+    }
+    if !settings.is_changed() && !input_map.is_changed() && !preferences.is_changed() {
+        return;
+    }
+
+    ConfigFile {
+        settings: settings.clone(),
+        controls: input_map.clone(),
+        preferences: preferences.clone(),
+    }
+    .save(&path);
+}
+
+pub(super) fn report_config_problem(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    mut problem: ResMut<ConfigProblem>,
+) {
+    if let Some(message) = problem.0.take() {
+        spawn_toast(&mut commands, &asset_server, &theme, &message);
+    }
+}
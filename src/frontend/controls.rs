@@ -0,0 +1,678 @@
+use super::gamepad::MenuFocus;
+use super::theme::Theme;
+use super::{player_index, AppState};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of columns that can have their own drop key.
+pub(super) const COLUMN_KEY_COUNT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(super) enum Action {
+    DropColumn(usize),
+    CursorLeft,
+    CursorRight,
+    Drop,
+    SelectItem,
+    AimUp,
+    AimDown,
+    Cancel,
+    Undo,
+    Pause,
+    Save,
+    Fullscreen,
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    FitBoard,
+}
+
+impl Action {
+    /// Actions each player binds separately.
+    fn player_actions() -> impl Iterator<Item = Action> {
+        (0..COLUMN_KEY_COUNT).map(Action::DropColumn).chain([
+            Action::CursorLeft,
+            Action::CursorRight,
+            Action::Drop,
+            Action::SelectItem,
+            Action::AimUp,
+            Action::AimDown,
+            Action::Cancel,
+        ])
+    }
+
+    /// Actions shared by both players.
+    const GLOBAL: [Action; 11] = [
+        Action::Undo,
+        Action::Pause,
+        Action::Save,
+        Action::Fullscreen,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::PanUp,
+        Action::PanDown,
+        Action::FitBoard,
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Action::DropColumn(col) => format!("Column {}", col + 1),
+            Action::CursorLeft => "Cursor left".to_string(),
+            Action::CursorRight => "Cursor right".to_string(),
+            Action::Drop => "Drop".to_string(),
+            Action::SelectItem => "Select power-up".to_string(),
+            Action::AimUp => "Aim up".to_string(),
+            Action::AimDown => "Aim down".to_string(),
+            Action::Cancel => "Cancel".to_string(),
+            Action::Undo => "Undo".to_string(),
+            Action::Pause => "Pause".to_string(),
+            Action::Save => "Save".to_string(),
+            Action::Fullscreen => "Fullscreen".to_string(),
+            Action::ZoomIn => "Zoom in".to_string(),
+            Action::ZoomOut => "Zoom out".to_string(),
+            Action::PanLeft => "Pan left".to_string(),
+            Action::PanRight => "Pan right".to_string(),
+            Action::PanUp => "Pan up".to_string(),
+            Action::PanDown => "Pan down".to_string(),
+            Action::FitBoard => "Fit board".to_string(),
+        }
+    }
+}
+
+/// Which binding a key belongs to: a player's own action or a shared one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct BindingSlot {
+    player: Option<usize>,
+    action: Action,
+}
+
+impl BindingSlot {
+    /// Whether keys of both could be pressed in the same game: shared
+    /// actions overlap everything, a player's only their own.
+    fn overlaps(&self, other: BindingSlot) -> bool {
+        self.player.is_none() || other.player.is_none() || self.player == other.player
+    }
+}
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub(super) struct InputMap {
+    players: [HashMap<Action, Vec<KeyCode>>; 2],
+    global: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let column_keys = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+            KeyCode::Key0,
+        ];
+
+        // Both players share the same keys by default, as they take turns
+        // at one keyboard.
+        let mut player = HashMap::new();
+        for (col, key) in column_keys.into_iter().enumerate() {
+            player.insert(Action::DropColumn(col), vec![key]);
+        }
+        player.insert(Action::CursorLeft, vec![KeyCode::Left, KeyCode::A]);
+        player.insert(Action::CursorRight, vec![KeyCode::Right, KeyCode::D]);
+        player.insert(
+            Action::Drop,
+            vec![KeyCode::Return, KeyCode::Space, KeyCode::Down],
+        );
+        player.insert(Action::SelectItem, vec![KeyCode::Tab, KeyCode::Q]);
+        player.insert(Action::AimUp, vec![KeyCode::Up, KeyCode::W]);
+        player.insert(Action::AimDown, vec![KeyCode::S]);
+        player.insert(Action::Cancel, vec![KeyCode::Back, KeyCode::X]);
+
+        let mut global = HashMap::new();
+        global.insert(Action::Undo, vec![KeyCode::Z]);
+        global.insert(Action::Pause, vec![KeyCode::Escape]);
+        global.insert(Action::Save, vec![KeyCode::F5]);
+        global.insert(Action::Fullscreen, vec![KeyCode::F11]);
+        global.insert(Action::ZoomIn, vec![KeyCode::Equals]);
+        global.insert(Action::ZoomOut, vec![KeyCode::Minus]);
+        global.insert(Action::PanLeft, vec![KeyCode::J]);
+        global.insert(Action::PanRight, vec![KeyCode::L]);
+        global.insert(Action::PanUp, vec![KeyCode::I]);
+        global.insert(Action::PanDown, vec![KeyCode::K]);
+        global.insert(Action::FitBoard, vec![KeyCode::Home]);
+
+        Self {
+            players: [player.clone(), player],
+            global,
+        }
+    }
+}
+
+impl InputMap {
+    /// Fills in defaults for any action the loaded bindings are missing,
+    /// e.g. actions added since the config was written.
+    pub(super) fn fill_missing(&mut self) {
+        let defaults = Self::default();
+        for (bindings, default_bindings) in self.players.iter_mut().zip(defaults.players) {
+            for (action, keys) in default_bindings {
+                bindings.entry(action).or_insert(keys);
+            }
+        }
+        for (action, keys) in defaults.global {
+            self.global.entry(action).or_insert(keys);
+        }
+    }
+
+    fn keys(&self, slot: BindingSlot) -> &[KeyCode] {
+        let bindings = match slot.player {
+            Some(player) => &self.players[player],
+            None => &self.global,
+        };
+        bindings.get(&slot.action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn keys_mut(&mut self, slot: BindingSlot) -> &mut Vec<KeyCode> {
+        let bindings = match slot.player {
+            Some(player) => &mut self.players[player],
+            None => &mut self.global,
+        };
+        bindings.entry(slot.action).or_default()
+    }
+
+    /// Every binding with at least one key.
+    fn slots(&self) -> Vec<BindingSlot> {
+        let players = self
+            .players
+            .iter()
+            .enumerate()
+            .flat_map(|(player, bindings)| {
+                bindings.keys().map(move |&action| BindingSlot {
+                    player: Some(player),
+                    action,
+                })
+            });
+        let global = self.global.keys().map(|&action| BindingSlot {
+            player: None,
+            action,
+        });
+        players.chain(global).collect()
+    }
+
+    /// Whether `key` is bound to a slot that can be pressed alongside `slot`:
+    /// one of the same player's, or a shared one.
+    fn clashes(&self, slot: BindingSlot, key: KeyCode) -> Option<BindingSlot> {
+        self.slots()
+            .into_iter()
+            .find(|&other| other != slot && other.overlaps(slot) && self.keys(other).contains(&key))
+    }
+
+    /// Binds `key` alone to `slot`. Bindings that could be pressed alongside
+    /// it give the key up and take `slot`'s old keys, where those are free.
+    /// Returns the bindings swapped that way.
+    fn bind(&mut self, slot: BindingSlot, key: KeyCode) -> Vec<BindingSlot> {
+        let old = std::mem::replace(self.keys_mut(slot), vec![key]);
+        let mut swapped = Vec::new();
+        while let Some(other) = self.clashes(slot, key) {
+            self.keys_mut(other).retain(|&bound| bound != key);
+            for &old_key in &old {
+                if self.clashes(other, old_key).is_none() {
+                    let keys = self.keys_mut(other);
+                    if !keys.contains(&old_key) {
+                        keys.push(old_key);
+                    }
+                }
+            }
+            swapped.push(other);
+        }
+        swapped
+    }
+
+    /// Whether `player` just pressed one of the keys bound to `action`.
+    pub(super) fn player_just_pressed(
+        &self,
+        player: char,
+        action: Action,
+        input: &Input<KeyCode>,
+    ) -> bool {
+        let slot = BindingSlot {
+            player: Some(player_index(player)),
+            action,
+        };
+        input.any_just_pressed(self.keys(slot).iter().copied())
+    }
+
+    /// Whether one of the keys bound to the shared `action` was just pressed.
+    pub(super) fn just_pressed(&self, action: Action, input: &Input<KeyCode>) -> bool {
+        let slot = BindingSlot {
+            player: None,
+            action,
+        };
+        input.any_just_pressed(self.keys(slot).iter().copied())
+    }
+
+    /// Whether one of the keys bound to the shared `action` is held down.
+    pub(super) fn pressed(&self, action: Action, input: &Input<KeyCode>) -> bool {
+        let slot = BindingSlot {
+            player: None,
+            action,
+        };
+        input.any_pressed(self.keys(slot).iter().copied())
+    }
+
+    fn describe(&self, slot: BindingSlot) -> String {
+        let keys = self.keys(slot);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        keys.iter()
+            .map(|&key| key_name(key))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match name.strip_prefix("Key") {
+        Some(rest) if rest.len() == 1 => rest.to_string(),
+        _ => name,
+    }
+}
+
+/// Binding waiting for its new key on the controls screen, and the ones
+/// that gave up the last key bound.
+#[derive(Resource, Default)]
+pub(super) struct Rebinding {
+    slot: Option<BindingSlot>,
+    swapped: Vec<BindingSlot>,
+}
+
+#[derive(Component)]
+pub(super) struct ControlsUI;
+
+#[derive(Component)]
+pub(super) struct BindingButton(BindingSlot);
+
+#[derive(Component)]
+pub(super) struct ControlsBackButton;
+
+#[derive(Component)]
+pub(super) struct ResetBindingsButton;
+
+pub(super) const ROW_HEIGHT: f32 = 30.0;
+
+pub(super) fn spawn_label(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    theme: &Theme,
+    text: &str,
+    width: f32,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(width),
+                height: Val::Px(ROW_HEIGHT),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|cell| {
+            cell.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font: theme.regular_font(asset_server),
+                    font_size: 20.0,
+                    color: theme.text,
+                },
+            ));
+        });
+}
+
+fn spawn_binding_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    input_map: &InputMap,
+    slot: BindingSlot,
+    theme: &Theme,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(170.0),
+                    height: Val::Px(ROW_HEIGHT - 4.0),
+                    margin: UiRect::all(Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.secondary_button.into(),
+                ..default()
+            },
+            BindingButton(slot),
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                input_map.describe(slot),
+                TextStyle {
+                    font: theme.bold_font(asset_server),
+                    font_size: 18.0,
+                    color: theme.text,
+                },
+            ));
+        });
+}
+
+pub(super) fn spawn_menu_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    theme: &Theme,
+    label: &str,
+    marker: impl Component,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(220.0),
+                    height: Val::Px(50.0),
+                    margin: UiRect::all(Val::Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.button.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: theme.bold_font(asset_server),
+                    font_size: 30.0,
+                    color: theme.text,
+                },
+            ));
+        });
+}
+
+pub(super) fn setup_controls_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    input_map: Res<InputMap>,
+    theme: Res<Theme>,
+) {
+    let row = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+    let column = |margin: f32| NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            margin: UiRect::horizontal(Val::Px(margin)),
+            ..default()
+        },
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.menu_background.into(),
+                ..default()
+            },
+            ControlsUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Controls",
+                    TextStyle {
+                        font: theme.bold_font(&asset_server),
+                        font_size: 50.0,
+                        color: theme.title,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+            );
+
+            parent.spawn(
+                TextBundle::from_section(
+                    "Click a binding, then press the new key. Escape cancels.",
+                    TextStyle {
+                        font: theme.regular_font(&asset_server),
+                        font_size: 20.0,
+                        color: theme.text,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(15.0)),
+                    ..default()
+                }),
+            );
+
+            parent.spawn(row()).with_children(|tables| {
+                tables.spawn(column(20.0)).with_children(|table| {
+                    table.spawn(row()).with_children(|header| {
+                        spawn_label(header, &asset_server, &theme, "", 120.0);
+                        spawn_label(header, &asset_server, &theme, "Player 1", 174.0);
+                        spawn_label(header, &asset_server, &theme, "Player 2", 174.0);
+                    });
+
+                    for action in Action::player_actions() {
+                        table.spawn(row()).with_children(|line| {
+                            spawn_label(line, &asset_server, &theme, &action.label(), 120.0);
+                            for player in 0..2 {
+                                let slot = BindingSlot {
+                                    player: Some(player),
+                                    action,
+                                };
+                                spawn_binding_button(line, &asset_server, &input_map, slot, &theme);
+                            }
+                        });
+                    }
+                });
+
+                tables.spawn(column(20.0)).with_children(|table| {
+                    table.spawn(row()).with_children(|header| {
+                        spawn_label(header, &asset_server, &theme, "Both players", 294.0);
+                    });
+
+                    for action in Action::GLOBAL {
+                        table.spawn(row()).with_children(|line| {
+                            spawn_label(line, &asset_server, &theme, &action.label(), 120.0);
+                            let slot = BindingSlot {
+                                player: None,
+                                action,
+                            };
+                            spawn_binding_button(line, &asset_server, &input_map, slot, &theme);
+                        });
+                    }
+
+                    table.spawn(NodeBundle {
+                        style: Style {
+                            height: Val::Px(30.0),
+                            ..default()
+                        },
+                        ..default()
+                    });
+
+                    spawn_menu_button(
+                        table,
+                        &asset_server,
+                        &theme,
+                        "Reset Defaults",
+                        ResetBindingsButton,
+                    );
+                    spawn_menu_button(table, &asset_server, &theme, "Back", ControlsBackButton);
+                });
+            });
+        });
+}
+
+pub(super) fn binding_button_system(
+    mut interaction_query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &mut BackgroundColor,
+        &BindingButton,
+    )>,
+    focus: Res<MenuFocus>,
+    mut rebinding: ResMut<Rebinding>,
+    theme: Res<Theme>,
+) {
+    for (entity, interaction, mut background_color, button) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(entity, interaction) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Pressed => {
+                *rebinding = Rebinding {
+                    slot: Some(button.0),
+                    swapped: Vec::new(),
+                };
+            }
+            _ if rebinding.slot == Some(button.0) => {
+                *background_color = theme.button_active.into();
+            }
+            Interaction::Hovered => {
+                *background_color = theme.secondary_button_hover.into();
+            }
+            Interaction::None => {
+                *background_color = theme.secondary_button.into();
+            }
+        }
+    }
+}
+
+pub(super) fn capture_rebinding_key(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Some(slot) = rebinding.slot else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        rebinding.slot = None;
+        return;
+    }
+
+    if let Some(&key) = keyboard_input.get_just_pressed().next() {
+        *rebinding = Rebinding {
+            slot: None,
+            swapped: input_map.bind(slot, key),
+        };
+    }
+}
+
+pub(super) fn update_binding_labels(
+    input_map: Res<InputMap>,
+    rebinding: Res<Rebinding>,
+    mut button_query: Query<(&BindingButton, &Children, &mut BackgroundColor)>,
+    mut text_query: Query<&mut Text>,
+    theme: Res<Theme>,
+) {
+    if !input_map.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    for (button, children, mut background_color) in &mut button_query {
+        let waiting = rebinding.slot == Some(button.0);
+        let label = if waiting {
+            "Press a key...".to_string()
+        } else if rebinding.swapped.contains(&button.0) {
+            format!("{} (swapped)", input_map.describe(button.0))
+        } else {
+            input_map.describe(button.0)
+        };
+
+        *background_color = if waiting {
+            theme.button_active.into()
+        } else {
+            theme.secondary_button.into()
+        };
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+pub(super) fn controls_menu_button_system(
+    mut interaction_query: Query<
+        (
+            Entity,
+            Ref<Interaction>,
+            &mut BackgroundColor,
+            Option<&ControlsBackButton>,
+        ),
+        Or<(With<ControlsBackButton>, With<ResetBindingsButton>)>,
+    >,
+    focus: Res<MenuFocus>,
+    mut input_map: ResMut<InputMap>,
+    mut rebinding: ResMut<Rebinding>,
+    mut app_state: ResMut<NextState<AppState>>,
+    theme: Res<Theme>,
+) {
+    for (entity, interaction, mut background_color, back_button) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(entity, interaction) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Pressed => {
+                *background_color = theme.button_pressed.into();
+                *rebinding = Rebinding::default();
+                if back_button.is_some() {
+                    app_state.set(AppState::Settings);
+                } else {
+                    *input_map = InputMap::default();
+                }
+            }
+            Interaction::Hovered => {
+                *background_color = theme.button_hover.into();
+            }
+            Interaction::None => {
+                *background_color = theme.button.into();
+            }
+        }
+    }
+}
+
+pub(super) fn cleanup_controls_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<ControlsUI>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,256 @@
+use super::config::Preferences;
+use super::controls::{Action, InputMap};
+use super::settings::SettingsDraft;
+use bevy::prelude::*;
+use bevy::window::{
+    PresentMode, PrimaryWindow, WindowMode, WindowMoved, WindowPosition, WindowResized,
+};
+use serde::{Deserialize, Serialize};
+
+/// Window sizes offered by the resolution setting.
+const RESOLUTIONS: [(f32, f32); 7] = [
+    (1024.0, 768.0),
+    (1280.0, 720.0),
+    (1366.0, 768.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+    (2560.0, 1440.0),
+    (3840.0, 2160.0),
+];
+/// Smallest window size accepted from the config file.
+const MIN_SIZE: Vec2 = Vec2::new(320.0, 240.0);
+/// Seconds the window has to stay put before its size and position are
+/// remembered, so dragging it doesn't rewrite the config every frame.
+const SETTLE_TIME: f32 = 0.5;
+
+/// How the window is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(super) enum DisplayMode {
+    #[default]
+    Windowed,
+    /// A window without decorations covering the whole screen.
+    Borderless,
+    /// Exclusive fullscreen at the chosen resolution.
+    Fullscreen,
+}
+
+impl DisplayMode {
+    const ALL: [DisplayMode; 3] = [
+        DisplayMode::Windowed,
+        DisplayMode::Borderless,
+        DisplayMode::Fullscreen,
+    ];
+
+    pub(super) fn label(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+
+    /// The mode `step` places after this one, wrapping around.
+    pub(super) fn step(self, step: isize) -> Self {
+        let current = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0) as isize;
+        Self::ALL[(current + step).rem_euclid(Self::ALL.len() as isize) as usize]
+    }
+
+    fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::SizedFullscreen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct WindowConfig {
+    pub(super) mode: DisplayMode,
+    /// Size of the window, or the screen resolution in fullscreen.
+    pub(super) width: f32,
+    pub(super) height: f32,
+    /// Where the window was last placed, in physical pixels. `None` lets the
+    /// system place it.
+    pub(super) position: Option<IVec2>,
+    pub(super) vsync: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Windowed,
+            width: 1280.0,
+            height: 720.0,
+            position: None,
+            vsync: true,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Resets a size a hand-edited config could have broken.
+    pub(super) fn validate(&mut self) {
+        if !(self.width >= MIN_SIZE.x && self.height >= MIN_SIZE.y) {
+            let defaults = Self::default();
+            self.width = defaults.width;
+            self.height = defaults.height;
+        }
+    }
+
+    pub(super) fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+
+    pub(super) fn resolution_label(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+
+    /// Moves to the next or previous listed resolution. A size from resizing
+    /// the window steps to the nearest listed one.
+    pub(super) fn step_resolution(&mut self, step: isize) {
+        let count = RESOLUTIONS.len() as isize;
+        let area = self.width * self.height;
+        let current = RESOLUTIONS
+            .iter()
+            .position(|&size| size == (self.width, self.height));
+        let next = match current {
+            Some(index) => (index as isize + step).rem_euclid(count) as usize,
+            None if step > 0 => RESOLUTIONS
+                .iter()
+                .position(|&(width, height)| width * height > area)
+                .unwrap_or(0),
+            None => RESOLUTIONS
+                .iter()
+                .rposition(|&(width, height)| width * height < area)
+                .unwrap_or(RESOLUTIONS.len() - 1),
+        };
+        (self.width, self.height) = RESOLUTIONS[next];
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    /// The primary window as configured.
+    pub(super) fn to_window(&self) -> Window {
+        Window {
+            resolution: (self.width, self.height).into(),
+            mode: self.mode.window_mode(),
+            present_mode: self.present_mode(),
+            position: self
+                .position
+                .map_or(WindowPosition::Automatic, WindowPosition::At),
+            resizable: true,
+            ..default()
+        }
+    }
+}
+
+/// Puts window preference changes from the settings or the fullscreen key
+/// into effect. The board layout follows through the resize this causes.
+pub(super) fn apply_window_preferences(
+    preferences: Res<Preferences>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let config = &preferences.window;
+
+    // Only touch what differs, so remembering the window's own size and
+    // position doesn't feed back into it.
+    let mode = config.mode.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    if window.present_mode != config.present_mode() {
+        window.present_mode = config.present_mode();
+    }
+    // Borderless always covers the screen at its own resolution.
+    let size = Vec2::new(window.resolution.width(), window.resolution.height());
+    if config.mode != DisplayMode::Borderless && size != config.size() {
+        window.resolution.set(config.width, config.height);
+    }
+}
+
+/// Switches between windowed and borderless fullscreen.
+pub(super) fn toggle_fullscreen(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut preferences: ResMut<Preferences>,
+    mut draft: ResMut<SettingsDraft>,
+) {
+    if !input_map.just_pressed(Action::Fullscreen, &keyboard_input) {
+        return;
+    }
+
+    let window = &mut preferences.window;
+    window.mode = match window.mode {
+        DisplayMode::Windowed => DisplayMode::Borderless,
+        DisplayMode::Borderless | DisplayMode::Fullscreen => DisplayMode::Windowed,
+    };
+    // Keep an open settings screen from undoing it.
+    draft.set_window(window);
+}
+
+/// Window movement waiting to settle before it is remembered.
+#[derive(Default)]
+pub(super) struct PendingPlacement {
+    quiet_for: Option<f32>,
+    position: Option<IVec2>,
+}
+
+/// Remembers where the window is and how big it is while windowed, so the
+/// next launch opens it the same way.
+pub(super) fn remember_window_placement(
+    mut resized: EventReader<WindowResized>,
+    mut moved: EventReader<WindowMoved>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    time: Res<Time>,
+    mut pending: Local<PendingPlacement>,
+    mut preferences: ResMut<Preferences>,
+    mut draft: ResMut<SettingsDraft>,
+) {
+    if let Some(event) = moved.iter().last() {
+        pending.position = Some(event.position);
+        pending.quiet_for = Some(0.0);
+    }
+    if resized.iter().last().is_some() {
+        pending.quiet_for = Some(0.0);
+    }
+
+    let Some(quiet_for) = pending.quiet_for.as_mut() else {
+        return;
+    };
+    *quiet_for += time.delta_seconds();
+    if *quiet_for < SETTLE_TIME {
+        return;
+    }
+    pending.quiet_for = None;
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    // Fullscreen sizes say nothing about the window to come back to.
+    if preferences.window.mode != DisplayMode::Windowed || window.mode != WindowMode::Windowed {
+        return;
+    }
+
+    let mut config = preferences.window.clone();
+    config.width = window.resolution.width();
+    config.height = window.resolution.height();
+    config.position = pending.position.or(config.position);
+    if config != preferences.window {
+        draft.set_window(&config);
+        preferences.window = config;
+    }
+}
@@ -0,0 +1,274 @@
+use super::inventory::ItemSelection;
+use super::{
+    player_index, AppState, ColumnCursor, DropRequest, GameStateResource, PauseReason, PauseState,
+    TargetRequest,
+};
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Gamepads bound to the player seats: index 0 plays X, index 1 plays O.
+#[derive(Resource, Default)]
+pub(super) struct GamepadSeats {
+    seats: [Option<Gamepad>; 2],
+}
+
+impl GamepadSeats {
+    pub(super) fn gamepad_for(&self, player: char) -> Option<Gamepad> {
+        self.seats[player_index(player)]
+    }
+}
+
+/// The menu button focused with a controller. Kept apart from the mouse's
+/// `Interaction`, which Bevy overwrites every frame.
+#[derive(Resource, Default)]
+pub(super) struct MenuFocus {
+    /// Position of the focused button in top-to-bottom order, `None` while
+    /// the mouse is in charge.
+    index: Option<usize>,
+    focused: Option<Entity>,
+    /// Pressed with a controller this frame.
+    activated: Option<Entity>,
+    /// Buttons whose look changed this frame without the mouse touching them.
+    changed: Vec<Entity>,
+}
+
+impl MenuFocus {
+    /// How `button` is being used this frame by the mouse and the
+    /// controller together, or `None` if nothing changed since last frame.
+    pub(super) fn interaction(
+        &self,
+        button: Entity,
+        mouse: Ref<Interaction>,
+    ) -> Option<Interaction> {
+        if self.activated == Some(button) {
+            return Some(Interaction::Pressed);
+        }
+        if !mouse.is_changed() && !self.changed.contains(&button) {
+            return None;
+        }
+        Some(match *mouse {
+            Interaction::None if self.focused == Some(button) => Interaction::Hovered,
+            other => other,
+        })
+    }
+
+    /// Whether a button was pressed with a controller this frame.
+    pub(super) fn activated(&self) -> bool {
+        self.activated.is_some()
+    }
+
+    fn focus(&mut self, button: Option<Entity>) {
+        if self.focused != button {
+            self.changed.extend(self.focused);
+            self.changed.extend(button);
+            self.focused = button;
+        }
+    }
+}
+
+/// Turns stick deflection into one step per flick instead of one per frame.
+fn stick_flick(
+    axes: &Axis<GamepadAxis>,
+    gamepad: Gamepad,
+    axis_type: GamepadAxisType,
+    last_directions: &mut HashMap<GamepadAxis, i8>,
+) -> i8 {
+    let axis = GamepadAxis::new(gamepad, axis_type);
+    let value = axes.get(axis).unwrap_or(0.0);
+    let direction = if value > STICK_THRESHOLD {
+        1
+    } else if value < -STICK_THRESHOLD {
+        -1
+    } else {
+        0
+    };
+
+    let previous = last_directions.insert(axis, direction).unwrap_or(0);
+    if direction != previous {
+        direction
+    } else {
+        0
+    }
+}
+
+pub(super) fn assign_gamepad_seats(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    mut seats: ResMut<GamepadSeats>,
+    app_state: Res<State<AppState>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+    mut pause_reason: ResMut<PauseReason>,
+) {
+    for event in connection_events.iter() {
+        match event.connection {
+            GamepadConnection::Connected(_) => {
+                if seats.seats.contains(&Some(event.gamepad)) {
+                    continue;
+                }
+                if let Some(seat) = seats.seats.iter_mut().find(|seat| seat.is_none()) {
+                    *seat = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                let Some(index) = seats
+                    .seats
+                    .iter()
+                    .position(|seat| *seat == Some(event.gamepad))
+                else {
+                    continue;
+                };
+                seats.seats[index] = None;
+
+                // A seat without its controller can't move, so stop the game
+                // until the player reconnects or chooses to carry on without it.
+                if *app_state.get() == AppState::InGame {
+                    pause_reason.0 =
+                        Some(format!("Player {}'s controller disconnected.", index + 1));
+                    pause_state.set(PauseState::Paused);
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn gamepad_column_input(
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    seats: Res<GamepadSeats>,
+    state: Res<GameStateResource>,
+    mut cursor: ResMut<ColumnCursor>,
+    mut selection: ResMut<ItemSelection>,
+    mut drop_requests: EventWriter<DropRequest>,
+    mut target_requests: EventWriter<TargetRequest>,
+    mut last_directions: Local<HashMap<GamepadAxis, i8>>,
+) {
+    // Only the controller bound to the current player's seat may play.
+    let Some(gamepad) = seats.gamepad_for(state.game.get_current_player()) else {
+        return;
+    };
+
+    let cols = state.game.get_board()[0].len();
+    let flick = stick_flick(
+        &axes,
+        gamepad,
+        GamepadAxisType::LeftStickX,
+        &mut last_directions,
+    );
+    let left = buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadLeft));
+    let right = buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadRight));
+
+    if left || flick < 0 {
+        cursor.step(-1, cols);
+    } else if right || flick > 0 {
+        cursor.step(1, cols);
+    }
+
+    // The top face button picks a collected power-up, up and down aim it or
+    // a target, and the right face button calls either off.
+    if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::East)) {
+        if state.game.targeting().is_some() {
+            target_requests.send(TargetRequest { cell: None });
+        } else {
+            selection.clear();
+        }
+    }
+    if state.game.targeting().is_none()
+        && buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::North))
+    {
+        selection.cycle(&state.game);
+        if cursor.col.is_none() {
+            cursor.step(0, cols);
+        }
+    }
+    let rows = state.game.get_board().len();
+    if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadUp)) {
+        selection.aim(1, rows);
+    } else if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadDown)) {
+        selection.aim(-1, rows);
+    }
+
+    if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South)) {
+        if let Some(col) = cursor.col {
+            drop_requests.send(DropRequest { col });
+        }
+    }
+}
+
+pub(super) fn gamepad_menu_navigation(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    app_state: Res<State<AppState>>,
+    pause_state: Res<State<PauseState>>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut focus: ResMut<MenuFocus>,
+    button_query: Query<(Entity, &GlobalTransform, &ComputedVisibility), With<Button>>,
+    mut last_directions: Local<HashMap<GamepadAxis, i8>>,
+) {
+    // A button pressed last frame is drawn as focused again.
+    let focus = &mut *focus;
+    focus.changed.clear();
+    focus.changed.extend(focus.activated.take());
+
+    // The controller plays the game instead while it runs.
+    if *app_state.get() == AppState::InGame && *pause_state.get() == PauseState::Running {
+        return;
+    }
+
+    // Moving the mouse hands the menu back to it.
+    if !cursor_moved.is_empty() {
+        cursor_moved.clear();
+        focus.index = None;
+        focus.focus(None);
+        return;
+    }
+
+    let mut step = 0;
+    let mut pressed = false;
+    for gamepad in gamepads.iter() {
+        if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadUp)) {
+            step -= 1;
+        }
+        if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadDown)) {
+            step += 1;
+        }
+        // Stick up is positive, but menus are ordered top to bottom.
+        step -= stick_flick(
+            &axes,
+            gamepad,
+            GamepadAxisType::LeftStickY,
+            &mut last_directions,
+        ) as isize;
+        pressed |= buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South));
+    }
+
+    let mut menu_buttons: Vec<_> = button_query
+        .iter()
+        .filter(|(_, _, visibility)| visibility.is_visible())
+        .collect();
+    if menu_buttons.is_empty() {
+        return;
+    }
+    menu_buttons.sort_by(|(_, a, _), (_, b, _)| {
+        let (a, b) = (a.translation(), b.translation());
+        a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+    });
+
+    let count = menu_buttons.len() as isize;
+    let index = match focus.index {
+        // The first controller input only shows where the focus is.
+        None if step != 0 || pressed => 0,
+        None => return,
+        Some(index) => {
+            let index = (index.min(menu_buttons.len() - 1) as isize + step).rem_euclid(count);
+            if pressed {
+                focus.activated = Some(menu_buttons[index as usize].0);
+            }
+            index as usize
+        }
+    };
+    focus.index = Some(index);
+    focus.focus(Some(menu_buttons[index].0));
+}
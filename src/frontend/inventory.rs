@@ -0,0 +1,230 @@
+// inventory.rs
+// Collected power-ups: the HUD listing them, choosing one and aiming it.
+// Targeted power-ups waiting for a piece to destroy are aimed the same way.
+use super::controls::{Action, InputMap};
+use super::layout::BoardLayout;
+use super::theme::Theme;
+use super::{ColumnCursor, GameStateResource, GameUI, MainCamera, TargetRequest};
+use crate::game::Game;
+use crate::power_ups;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// The collected power-up the player to move is aiming, if any. While one
+/// is chosen, dropping in a column uses it there instead.
+#[derive(Resource, Default)]
+pub(super) struct ItemSelection {
+    /// Position in the player's inventory.
+    item: Option<usize>,
+    /// Row aimed at; the column is the column cursor's.
+    pub(super) row: usize,
+}
+
+impl ItemSelection {
+    /// The chosen power-up, if the player still has it.
+    pub(super) fn glyph(&self, game: &Game) -> Option<char> {
+        let inventory = game.inventory(game.get_current_player());
+        inventory.get(self.item?).copied()
+    }
+
+    /// Moves to the next collected power-up, then back to dropping pieces.
+    pub(super) fn cycle(&mut self, game: &Game) {
+        let count = game.inventory(game.get_current_player()).len();
+        self.item = match self.item {
+            None if count > 0 => Some(0),
+            Some(item) if item + 1 < count => Some(item + 1),
+            _ => None,
+        };
+    }
+
+    /// Moves the aim `step` rows, staying on the board.
+    pub(super) fn aim(&mut self, step: isize, rows: usize) {
+        self.row = self.row.saturating_add_signed(step).min(rows - 1);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.item = None;
+    }
+}
+
+/// Lists the current player's collected power-ups beside the turn
+/// indicator.
+#[derive(Component)]
+pub(super) struct InventoryPanel;
+
+/// Highlights the cell a power-up is aimed at, fainter when it can't go
+/// there.
+#[derive(Component)]
+pub(super) struct AimMarker;
+
+pub(super) fn spawn_panel(parent: &mut ChildBuilder, asset_server: &AssetServer, theme: &Theme) {
+    parent.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: theme.regular_font(asset_server),
+                font_size: 24.0,
+                color: theme.text,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::left(Val::Px(30.0)),
+            align_self: AlignSelf::Center,
+            ..default()
+        }),
+        InventoryPanel,
+    ));
+}
+
+pub(super) fn spawn_aim_marker(commands: &mut Commands, layout: &BoardLayout) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(layout.cell_size())),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 2.6),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        AimMarker,
+        GameUI,
+    ));
+}
+
+/// Choosing and aiming collected power-ups and targets from the keyboard and
+/// mouse.
+pub(super) fn item_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    state: Res<GameStateResource>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    layout: Res<BoardLayout>,
+    mut cursor: ResMut<ColumnCursor>,
+    mut selection: ResMut<ItemSelection>,
+    mut target_requests: EventWriter<TargetRequest>,
+) {
+    let player = state.game.get_current_player();
+    let rows = state.game.get_board().len();
+    let targeting = state.game.targeting();
+
+    if input_map.player_just_pressed(player, Action::Cancel, &keyboard_input) {
+        if targeting.is_some() {
+            target_requests.send(TargetRequest { cell: None });
+        } else {
+            selection.clear();
+        }
+    }
+    if let Some(targeting) = targeting {
+        // Start the aim on the power-up that is waiting.
+        cursor.col.get_or_insert(targeting.col);
+    } else if input_map.player_just_pressed(player, Action::SelectItem, &keyboard_input) {
+        selection.cycle(&state.game);
+        if cursor.col.is_none() {
+            cursor.step(0, state.game.get_board()[0].len());
+        }
+    }
+    if selection.glyph(&state.game).is_none() && targeting.is_none() {
+        cursor_moved.clear();
+        return;
+    }
+
+    if input_map.player_just_pressed(player, Action::AimUp, &keyboard_input) {
+        selection.aim(1, rows);
+    } else if input_map.player_just_pressed(player, Action::AimDown, &keyboard_input) {
+        selection.aim(-1, rows);
+    }
+
+    // The column follows the mouse in `mouse_column_input`; the row here.
+    if cursor_moved.is_empty() {
+        return;
+    }
+    cursor_moved.clear();
+    let (Ok(window), Ok((camera, camera_transform))) =
+        (window_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+    let hovered_row = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        .and_then(|world| layout.row_at(world));
+    if let Some(row) = hovered_row {
+        selection.row = row;
+    }
+}
+
+pub(super) fn update_inventory_panel(
+    state: Res<GameStateResource>,
+    selection: Res<ItemSelection>,
+    mut panel_query: Query<&mut Text, With<InventoryPanel>>,
+) {
+    if !state.is_changed() && !selection.is_changed() {
+        return;
+    }
+    let Ok(mut text) = panel_query.get_single_mut() else {
+        return;
+    };
+    if let Some(targeting) = state.game.targeting() {
+        text.sections[0].value =
+            format!("Pick a piece within {} cells to destroy", targeting.radius);
+        return;
+    }
+
+    let inventory = state.game.inventory(state.game.get_current_player());
+    let chosen = selection.glyph(&state.game).and(selection.item);
+    text.sections[0].value = if inventory.is_empty() {
+        String::new()
+    } else {
+        let items: Vec<String> = inventory
+            .iter()
+            .enumerate()
+            .map(|(index, &glyph)| {
+                let name = power_ups::registry()
+                    .get(glyph)
+                    .map_or_else(|| glyph.to_string(), |power_up| power_up.name().to_string());
+                if chosen == Some(index) {
+                    format!("[{}]", name)
+                } else {
+                    name
+                }
+            })
+            .collect();
+        format!("Power-ups: {}", items.join("  "))
+    };
+}
+
+pub(super) fn update_aim_marker(
+    state: Res<GameStateResource>,
+    layout: Res<BoardLayout>,
+    cursor: Res<ColumnCursor>,
+    selection: Res<ItemSelection>,
+    mut marker_query: Query<(&mut Transform, &mut Visibility, &mut Sprite), With<AimMarker>>,
+    theme: Res<Theme>,
+) {
+    let Ok((mut transform, mut visibility, mut sprite)) = marker_query.get_single_mut() else {
+        return;
+    };
+
+    let targeting = state.game.targeting();
+    let aimed = targeting
+        .map(|targeting| targeting.glyph)
+        .or_else(|| selection.glyph(&state.game))
+        .and_then(|glyph| power_ups::registry().get(glyph))
+        .zip(cursor.col);
+    let Some((power_up, col)) = aimed else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let row = selection.row.min(layout.rows() - 1);
+    let allowed = targeting.is_none() || state.game.targets().contains(&(row, col));
+    transform.translation = layout
+        .cell_position(row, col)
+        .extend(transform.translation.z);
+    let alpha = if allowed { 0.45 } else { 0.15 };
+    sprite.color = theme.power_up_color(power_up).with_a(alpha);
+    *visibility = Visibility::Visible;
+}
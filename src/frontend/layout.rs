@@ -0,0 +1,197 @@
+use crate::game::Game;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+const CELL_SIZE: f32 = 75.0;
+const CELL_PADDING: f32 = 7.5;
+/// The board sits below the centre of the view to leave room for the turn
+/// indicator.
+const BOARD_OFFSET_Y: f32 = -50.0;
+/// World space kept around the board for the column labels and selector.
+const VIEW_MARGIN: f32 = 200.0;
+/// Window size the HUD was designed at; it scales from there.
+const REFERENCE_WINDOW: Vec2 = Vec2::new(1280.0, 720.0);
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 2.0;
+/// Room the game over screen needs above and below its board thumbnail, in
+/// unscaled UI pixels.
+const GAME_OVER_CHROME: f32 = 330.0;
+const MAX_THUMBNAIL_CELL: f32 = 40.0;
+const MIN_THUMBNAIL_CELL: f32 = 8.0;
+
+/// Where everything on the board is drawn, in world units, and how the
+/// window fits it.
+///
+/// `rows` and `cols` are the size of the board as drawn. They lag behind the
+/// game after an expansion until the view has grown to match.
+#[derive(Resource, Debug, Clone, Copy)]
+pub(super) struct BoardLayout {
+    rows: usize,
+    cols: usize,
+    /// Logical size of the primary window.
+    window: Vec2,
+}
+
+impl BoardLayout {
+    pub(super) fn new(game: &Game, window: Vec2) -> Self {
+        let mut layout = Self {
+            rows: 0,
+            cols: 0,
+            window,
+        };
+        layout.fit(game);
+        layout
+    }
+
+    /// Lays the board out at the size of `game`.
+    pub(super) fn fit(&mut self, game: &Game) {
+        self.rows = game.get_board().len();
+        self.cols = game.get_board()[0].len();
+    }
+
+    /// Whether the board is drawn at the size of `game`.
+    pub(super) fn fits(&self, game: &Game) -> bool {
+        (self.rows, self.cols) == (game.get_board().len(), game.get_board()[0].len())
+    }
+
+    pub(super) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub(super) fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub(super) fn cell_size(&self) -> f32 {
+        CELL_SIZE
+    }
+
+    /// Size of the grid of cells, without the frame around it.
+    pub(super) fn board_size(&self) -> Vec2 {
+        Vec2::new(
+            self.cols as f32 * (CELL_SIZE + CELL_PADDING) - CELL_PADDING,
+            self.rows as f32 * (CELL_SIZE + CELL_PADDING) - CELL_PADDING,
+        )
+    }
+
+    pub(super) fn frame_size(&self) -> Vec2 {
+        self.board_size() + CELL_PADDING * 2.0
+    }
+
+    /// Centre of the board.
+    pub(super) fn center(&self) -> Vec2 {
+        Vec2::new(0.0, BOARD_OFFSET_Y)
+    }
+
+    pub(super) fn column_x(&self, col: usize) -> f32 {
+        col as f32 * (CELL_SIZE + CELL_PADDING) - self.board_size().x / 2.0 + CELL_SIZE / 2.0
+    }
+
+    pub(super) fn row_y(&self, row: usize) -> f32 {
+        row as f32 * (CELL_SIZE + CELL_PADDING) - self.board_size().y / 2.0
+            + CELL_SIZE / 2.0
+            + BOARD_OFFSET_Y
+    }
+
+    pub(super) fn cell_position(&self, row: usize, col: usize) -> Vec2 {
+        Vec2::new(self.column_x(col), self.row_y(row))
+    }
+
+    /// Height falling pieces start from, just above the board.
+    pub(super) fn drop_y(&self) -> f32 {
+        self.board_size().y / 2.0 + CELL_SIZE + BOARD_OFFSET_Y
+    }
+
+    pub(super) fn label_y(&self) -> f32 {
+        self.board_size().y / 2.0 + 40.0 + BOARD_OFFSET_Y
+    }
+
+    pub(super) fn selector_y(&self) -> f32 {
+        self.board_size().y / 2.0 + 80.0 + BOARD_OFFSET_Y
+    }
+
+    /// Top edge of the view.
+    pub(super) fn view_top(&self) -> f32 {
+        self.view_size().y / 2.0
+    }
+
+    /// Column under a point in world space: anywhere over the board or its
+    /// labels.
+    pub(super) fn column_at(&self, world: Vec2) -> Option<usize> {
+        let board = self.board_size();
+        let left = -board.x / 2.0 - CELL_PADDING / 2.0;
+        let bottom = -board.y / 2.0 - CELL_PADDING + BOARD_OFFSET_Y;
+        let top = self.label_y() + CELL_SIZE / 2.0;
+
+        if world.x < left || world.y < bottom || world.y > top {
+            return None;
+        }
+
+        let col = ((world.x - left) / (CELL_SIZE + CELL_PADDING)) as usize;
+        (col < self.cols).then_some(col)
+    }
+
+    /// Row of the cell under a point in world space, if it is over the
+    /// board.
+    pub(super) fn row_at(&self, world: Vec2) -> Option<usize> {
+        let bottom = -self.board_size().y / 2.0 - CELL_PADDING / 2.0 + BOARD_OFFSET_Y;
+        if world.y < bottom {
+            return None;
+        }
+        let row = ((world.y - bottom) / (CELL_SIZE + CELL_PADDING)) as usize;
+        (row < self.rows).then_some(row)
+    }
+
+    /// World area the camera keeps in view.
+    pub(super) fn view_size(&self) -> Vec2 {
+        self.board_size() + VIEW_MARGIN
+    }
+
+    /// World units per window pixel once the view is fitted to the window.
+    pub(super) fn zoom(&self) -> f32 {
+        let zoom = self.view_size() / self.window.max(Vec2::ONE);
+        zoom.max_element()
+    }
+
+    /// Scale for the HUD and menus, relative to the window they were
+    /// designed at.
+    pub(super) fn ui_scale(&self) -> f32 {
+        (self.window / REFERENCE_WINDOW)
+            .min_element()
+            .clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+    }
+
+    /// Cell size for the board thumbnail on the game over screen, so the
+    /// whole screen fits the window.
+    pub(super) fn thumbnail_cell_size(&self) -> f32 {
+        let available = self.window / self.ui_scale();
+        let width = available.x * 0.8 / self.cols as f32;
+        let height = (available.y - GAME_OVER_CHROME) / self.rows as f32;
+        width
+            .min(height)
+            .clamp(MIN_THUMBNAIL_CELL, MAX_THUMBNAIL_CELL)
+    }
+}
+
+/// Follows the primary window's size and rescales the UI to match.
+pub(super) fn fit_to_window(
+    mut resized: EventReader<WindowResized>,
+    primary_window: Query<(), With<PrimaryWindow>>,
+    mut layout: ResMut<BoardLayout>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Some(size) = resized
+        .iter()
+        .filter(|event| primary_window.contains(event.window))
+        .last()
+        .map(|event| Vec2::new(event.width, event.height))
+    else {
+        return;
+    };
+    if size == layout.window {
+        return;
+    }
+
+    layout.window = size;
+    ui_scale.scale = layout.ui_scale() as f64;
+}
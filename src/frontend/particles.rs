@@ -0,0 +1,382 @@
+use super::config::Preferences;
+use super::layout::BoardLayout;
+use super::theme::Theme;
+use super::{PieceLanded, PowerUpActivated};
+use crate::power_ups::Cue;
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::f32::consts::{PI, TAU};
+
+/// Particles that can be alive at once. Bursts beyond this are cut short.
+const MAX_PARTICLES: usize = 512;
+/// Above pieces and obstacles.
+const PARTICLE_Z: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Burst {
+    /// Sparks, debris and smoke in the power-up's color.
+    Bomb { color: Color },
+    /// A shockwave ring in the power-up's color and its icon.
+    Skip {
+        color: Color,
+        icon: Option<&'static str>,
+    },
+    /// Dust kicked up by a landing obstacle.
+    Dust,
+    /// Confetti falling from above.
+    Confetti,
+}
+
+/// Asks for a burst of particles centred on `position`.
+#[derive(Event)]
+pub(super) struct ParticleBurst {
+    pub(super) burst: Burst,
+    pub(super) position: Vec2,
+}
+
+/// How one particle starts out and changes over its life.
+struct Spawn {
+    velocity: Vec2,
+    /// Downward acceleration; negative floats upwards.
+    gravity: f32,
+    /// Share of the velocity lost per second.
+    drag: f32,
+    /// Turns per second, in radians.
+    spin: f32,
+    lifetime: f32,
+    size: (f32, f32),
+    color: Color,
+    texture: Handle<Image>,
+}
+
+impl Default for Spawn {
+    fn default() -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            gravity: 0.0,
+            drag: 0.0,
+            spin: 0.0,
+            lifetime: 1.0,
+            size: (10.0, 10.0),
+            color: Color::WHITE,
+            texture: Handle::default(),
+        }
+    }
+}
+
+/// A pooled sprite. Inactive particles are hidden and wait in [`ParticlePool`].
+#[derive(Component, Default)]
+pub(super) struct Particle {
+    active: bool,
+    velocity: Vec2,
+    gravity: f32,
+    drag: f32,
+    spin: f32,
+    age: f32,
+    lifetime: f32,
+    size: (f32, f32),
+    /// Starting color; the alpha fades to 0 over the particle's life.
+    color: Color,
+}
+
+/// Sprites free to be reused by the next burst.
+#[derive(Resource, Default)]
+pub(super) struct ParticlePool {
+    free: Vec<Entity>,
+}
+
+fn random_direction(rng: &mut impl Rng, from: f32, to: f32) -> Vec2 {
+    Vec2::from_angle(rng.gen_range(from..to))
+}
+
+impl Burst {
+    fn particles(&self, theme: &Theme, asset_server: &AssetServer) -> Vec<Spawn> {
+        let mut rng = rand::thread_rng();
+        let mut spawns = Vec::new();
+
+        match self {
+            Burst::Bomb { color } => {
+                spawns.push(Spawn {
+                    lifetime: 0.25,
+                    size: (40.0, 140.0),
+                    color: color.with_a(0.6),
+                    ..default()
+                });
+                for _ in 0..24 {
+                    spawns.push(Spawn {
+                        velocity: random_direction(&mut rng, 0.0, TAU)
+                            * rng.gen_range(300.0..700.0),
+                        drag: 3.0,
+                        lifetime: rng.gen_range(0.3..0.6),
+                        size: (8.0, 2.0),
+                        color: *[*color, theme.title].choose(&mut rng).unwrap(),
+                        ..default()
+                    });
+                }
+                for _ in 0..14 {
+                    let size = rng.gen_range(10.0..16.0);
+                    spawns.push(Spawn {
+                        velocity: random_direction(&mut rng, 0.1 * PI, 0.9 * PI)
+                            * rng.gen_range(200.0..450.0),
+                        gravity: 1500.0,
+                        drag: 0.5,
+                        spin: rng.gen_range(-10.0..10.0),
+                        lifetime: rng.gen_range(0.8..1.2),
+                        size: (size, size),
+                        color: *[theme.board, theme.obstacle].choose(&mut rng).unwrap(),
+                        ..default()
+                    });
+                }
+                for _ in 0..8 {
+                    spawns.push(Spawn {
+                        velocity: random_direction(&mut rng, 0.3 * PI, 0.7 * PI)
+                            * rng.gen_range(20.0..60.0),
+                        gravity: -40.0,
+                        drag: 1.0,
+                        lifetime: rng.gen_range(1.0..1.6),
+                        size: (30.0, 70.0),
+                        color: theme.text.with_a(0.25),
+                        ..default()
+                    });
+                }
+            }
+            Burst::Skip { color, icon } => {
+                let count = 28;
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * TAU;
+                    spawns.push(Spawn {
+                        velocity: Vec2::from_angle(angle) * 350.0,
+                        drag: 2.5,
+                        lifetime: 0.5,
+                        size: (8.0, 3.0),
+                        color: *color,
+                        ..default()
+                    });
+                }
+                if let Some(icon) = icon {
+                    spawns.push(Spawn {
+                        velocity: Vec2::Y * 60.0,
+                        lifetime: 0.9,
+                        size: (50.0, 80.0),
+                        texture: asset_server.load(*icon),
+                        ..default()
+                    });
+                }
+            }
+            Burst::Dust => {
+                for _ in 0..12 {
+                    // Out to either side, close to the ground.
+                    let angle = rng.gen_range(0.0..0.15 * PI);
+                    let angle = if rng.gen() { angle } else { PI - angle };
+                    spawns.push(Spawn {
+                        velocity: Vec2::from_angle(angle) * rng.gen_range(80.0..200.0),
+                        gravity: -20.0,
+                        drag: 2.5,
+                        lifetime: rng.gen_range(0.5..0.9),
+                        size: (10.0, 24.0),
+                        color: theme.obstacle.with_a(0.5),
+                        ..default()
+                    });
+                }
+            }
+            Burst::Confetti => {
+                let colors: Vec<Color> = theme
+                    .players
+                    .iter()
+                    .chain(&theme.column_labels)
+                    .copied()
+                    .collect();
+                for _ in 0..30 {
+                    let size = rng.gen_range(8.0..14.0);
+                    spawns.push(Spawn {
+                        velocity: Vec2::new(
+                            rng.gen_range(-150.0..150.0),
+                            rng.gen_range(-250.0..50.0),
+                        ),
+                        gravity: 200.0,
+                        drag: 0.8,
+                        spin: rng.gen_range(-8.0..8.0),
+                        lifetime: rng.gen_range(2.5..3.5),
+                        size: (size, size),
+                        color: *colors.choose(&mut rng).unwrap_or(&theme.title),
+                        ..default()
+                    });
+                }
+            }
+        }
+        spawns
+    }
+}
+
+pub(super) fn setup_particle_pool(mut commands: Commands, mut pool: ResMut<ParticlePool>) {
+    for _ in 0..MAX_PARTICLES {
+        let entity = commands
+            .spawn((
+                SpriteBundle {
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                Particle::default(),
+            ))
+            .id();
+        pool.free.push(entity);
+    }
+}
+
+pub(super) fn emit_particles(
+    mut bursts: EventReader<ParticleBurst>,
+    mut pool: ResMut<ParticlePool>,
+    mut particles: Query<(
+        &mut Particle,
+        &mut Sprite,
+        &mut Handle<Image>,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    preferences: Res<Preferences>,
+) {
+    for burst in bursts.iter() {
+        if preferences.reduced_motion {
+            continue;
+        }
+
+        for spawn in burst.burst.particles(&theme, &asset_server) {
+            let Some(entity) = pool.free.pop() else {
+                return;
+            };
+            let Ok((mut particle, mut sprite, mut texture, mut transform, mut visibility)) =
+                particles.get_mut(entity)
+            else {
+                continue;
+            };
+
+            *particle = Particle {
+                active: true,
+                velocity: spawn.velocity,
+                gravity: spawn.gravity,
+                drag: spawn.drag,
+                spin: spawn.spin,
+                age: 0.0,
+                lifetime: spawn.lifetime,
+                size: spawn.size,
+                color: spawn.color,
+            };
+            sprite.color = spawn.color;
+            sprite.custom_size = Some(Vec2::splat(spawn.size.0));
+            *texture = spawn.texture;
+            *transform = Transform::from_translation(burst.position.extend(PARTICLE_Z));
+            *visibility = Visibility::Visible;
+        }
+    }
+}
+
+pub(super) fn update_particles(
+    mut pool: ResMut<ParticlePool>,
+    mut particles: Query<(
+        Entity,
+        &mut Particle,
+        &mut Sprite,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut particle, mut sprite, mut transform, mut visibility) in &mut particles {
+        if !particle.active {
+            continue;
+        }
+
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            particle.active = false;
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+
+        let gravity = particle.gravity;
+        let drag = (1.0 - particle.drag * dt).max(0.0);
+        particle.velocity.y -= gravity * dt;
+        particle.velocity *= drag;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        transform.rotate_z(particle.spin * dt);
+
+        let progress = particle.age / particle.lifetime;
+        let (start, end) = particle.size;
+        sprite.custom_size = Some(Vec2::splat(start + (end - start) * progress));
+        sprite.color = particle.color.with_a(particle.color.a() * (1.0 - progress));
+    }
+}
+
+pub(super) fn power_up_particles(
+    mut events: EventReader<PowerUpActivated>,
+    layout: Res<BoardLayout>,
+    theme: Res<Theme>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    for event in events.iter() {
+        if event.effects.is_empty() {
+            continue;
+        }
+        let color = theme.power_up_color(event.power_up);
+        let burst = match event.power_up.visual().cue {
+            Cue::Explosion => Burst::Bomb { color },
+            Cue::Shockwave => Burst::Skip {
+                color,
+                icon: event.power_up.visual().icon.as_deref(),
+            },
+            // The dust comes when the obstacles land.
+            Cue::Rumble => continue,
+        };
+        bursts.send(ParticleBurst {
+            burst,
+            position: layout.cell_position(event.row, event.col),
+        });
+    }
+}
+
+pub(super) fn landing_dust(
+    mut landings: EventReader<PieceLanded>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    for landing in landings.iter() {
+        if landing.obstacle {
+            bursts.send(ParticleBurst {
+                burst: Burst::Dust,
+                position: landing.position - Vec2::Y * 35.0,
+            });
+        }
+    }
+}
+
+/// Confetti along the top of the view.
+pub(super) fn win_confetti(layout: &BoardLayout, bursts: &mut EventWriter<ParticleBurst>) {
+    let board_width = layout.board_size().x;
+    let top = layout.view_top() - 50.0;
+    let count = 8;
+    for i in 0..count {
+        let x = (i as f32 + 0.5) / count as f32 * board_width - board_width / 2.0;
+        bursts.send(ParticleBurst {
+            burst: Burst::Confetti,
+            position: Vec2::new(x, top),
+        });
+    }
+}
+
+/// Puts every particle back in the pool when leaving the board.
+pub(super) fn clear_particles(
+    mut pool: ResMut<ParticlePool>,
+    mut particles: Query<(Entity, &mut Particle, &mut Visibility)>,
+) {
+    for (entity, mut particle, mut visibility) in &mut particles {
+        if particle.active {
+            particle.active = false;
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use super::config::Preferences;
+use super::controls::{Action, InputMap};
+use super::gamepad::MenuFocus;
+use super::inventory::ItemSelection;
+use super::layout::BoardLayout;
+use super::settings::{Settings, SettingsDraft};
+use super::skin::LoadedSkin;
+use super::theme::Theme;
+use super::{
+    rebuild_board_view, save_game, AppState, ChainReaction, ColumnCursor, GameStateResource,
+    GameUI, MainCameraQuery, PauseReason, PauseState,
+};
+use crate::game::Game;
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub(super) struct PauseUI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseAction {
+    Resume,
+    Restart,
+    Save,
+    Settings,
+    Quit,
+}
+
+impl PauseAction {
+    const ALL: [PauseAction; 5] = [
+        PauseAction::Resume,
+        PauseAction::Restart,
+        PauseAction::Save,
+        PauseAction::Settings,
+        PauseAction::Quit,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PauseAction::Resume => "Resume",
+            PauseAction::Restart => "Restart",
+            PauseAction::Save => "Save",
+            PauseAction::Settings => "Settings",
+            PauseAction::Quit => "Quit to Menu",
+        }
+    }
+}
+
+#[derive(Component)]
+pub(super) struct PauseMenuButton(PauseAction);
+
+pub(super) fn setup_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    reason: Res<PauseReason>,
+    theme: Res<Theme>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.overlay.into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            PauseUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Paused",
+                    TextStyle {
+                        font: theme.bold_font(&asset_server),
+                        font_size: 70.0,
+                        color: theme.title,
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                ..default()
+            });
+
+            if let Some(reason) = &reason.0 {
+                parent.spawn(TextBundle {
+                    text: Text::from_section(
+                        reason.clone(),
+                        TextStyle {
+                            font: theme.regular_font(&asset_server),
+                            font_size: 30.0,
+                            color: theme.text,
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    ..default()
+                });
+            }
+
+            parent.spawn(NodeBundle {
+                style: Style {
+                    height: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            });
+
+            for action in PauseAction::ALL {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(260.0),
+                                height: Val::Px(55.0),
+                                margin: UiRect::all(Val::Px(8.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: theme.button.into(),
+                            ..default()
+                        },
+                        PauseMenuButton(action),
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle {
+                            text: Text::from_section(
+                                action.label(),
+                                TextStyle {
+                                    font: theme.bold_font(&asset_server),
+                                    font_size: 34.0,
+                                    color: theme.text,
+                                },
+                            )
+                            .with_alignment(TextAlignment::Center),
+                            ..default()
+                        });
+                    });
+            }
+        });
+}
+
+pub(super) fn resume_game(
+    keyboard_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+) {
+    let start_pressed = gamepad_buttons
+        .get_just_pressed()
+        .any(|button| button.button_type == GamepadButtonType::Start);
+
+    if input_map.just_pressed(Action::Pause, &keyboard_input) || start_pressed {
+        pause_state.set(PauseState::Running);
+    }
+}
+
+pub(super) fn pause_menu_button_system(
+    mut interaction_query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &mut BackgroundColor,
+        &PauseMenuButton,
+    )>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+    mut app_state: ResMut<NextState<AppState>>,
+    // Grouped to stay within the system parameter limit.
+    (mut state, mut layout, focus): (
+        ResMut<GameStateResource>,
+        ResMut<BoardLayout>,
+        Res<MenuFocus>,
+    ),
+    settings: Res<Settings>,
+    preferences: Res<Preferences>,
+    mut draft: ResMut<SettingsDraft>,
+    mut cursor: ResMut<ColumnCursor>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    game_ui_query: Query<Entity, With<GameUI>>,
+    mut camera_query: MainCameraQuery,
+    theme: Res<Theme>,
+    skin: Res<LoadedSkin>,
+) {
+    for (entity, interaction, mut background_color, button) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(entity, interaction) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Pressed => {
+                *background_color = theme.button_pressed.into();
+            }
+            Interaction::Hovered => {
+                *background_color = theme.button_hover.into();
+                continue;
+            }
+            Interaction::None => {
+                *background_color = theme.button.into();
+                continue;
+            }
+        }
+
+        match button.0 {
+            PauseAction::Resume => {
+                pause_state.set(PauseState::Running);
+            }
+            PauseAction::Restart => {
+                // The same rules as the game being played, which may have
+                // been loaded or started before the settings changed.
+                state.game = Game::with_settings(state.game.settings().clone());
+                state.history.clear();
+                cursor.col = None;
+                commands.insert_resource(ChainReaction::default());
+                commands.insert_resource(ItemSelection::default());
+                rebuild_board_view(
+                    &mut commands,
+                    &state,
+                    &mut layout,
+                    &asset_server,
+                    &mut meshes,
+                    &mut materials,
+                    &game_ui_query,
+                    &mut camera_query,
+                    &theme,
+                    &skin,
+                );
+                pause_state.set(PauseState::Running);
+            }
+            PauseAction::Save => {
+                save_game(&mut commands, &asset_server, &theme, &state.game);
+            }
+            PauseAction::Settings => {
+                // Leaving `InGame` tears the board down; `resume` brings the
+                // same game back once the settings screen is closed.
+                state.resume = true;
+                *draft = SettingsDraft::new(&settings, &preferences);
+                pause_state.set(PauseState::Running);
+                app_state.set(AppState::Settings);
+            }
+            PauseAction::Quit => {
+                state.resume = false;
+                pause_state.set(PauseState::Running);
+                app_state.set(AppState::MainMenu);
+            }
+        }
+    }
+}
+
+pub(super) fn cleanup_pause_menu(
+    mut commands: Commands,
+    query: Query<Entity, With<PauseUI>>,
+    mut reason: ResMut<PauseReason>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+    reason.0 = None;
+}
@@ -0,0 +1,727 @@
+use super::config::Preferences;
+use super::controls::{spawn_label, spawn_menu_button, ROW_HEIGHT};
+use super::display::WindowConfig;
+use super::gamepad::MenuFocus;
+use super::skin::Skins;
+use super::theme::{Theme, Themes};
+use super::{player_index, AppState, GameStateResource};
+use crate::ai::Difficulty;
+use crate::game::{
+    GameSettings, Respawn, MAX_BOARD_SIZE, MIN_BOARD_SIZE, MIN_WIN_LENGTH, PLAYER_O, PLAYER_X,
+};
+use crate::power_ups;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Who plays a seat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum Seat {
+    Human,
+    Computer(Difficulty),
+}
+
+impl Seat {
+    const ALL: [Seat; 4] = [
+        Seat::Human,
+        Seat::Computer(Difficulty::Easy),
+        Seat::Computer(Difficulty::Medium),
+        Seat::Computer(Difficulty::Hard),
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Seat::Human => "Human".to_string(),
+            Seat::Computer(difficulty) => format!("Computer ({})", difficulty.label()),
+        }
+    }
+}
+
+/// Placements offered in the settings, as (mirrored, parity balanced).
+const PLACEMENT_CHOICES: [(bool, bool); 4] =
+    [(false, false), (true, false), (false, true), (true, true)];
+
+fn placement_label(mirrored: bool, parity_balanced: bool) -> String {
+    match (mirrored, parity_balanced) {
+        (false, false) => "Random",
+        (true, false) => "Mirrored",
+        (false, true) => "Balanced rows",
+        (true, true) => "Mirrored, balanced",
+    }
+    .to_string()
+}
+
+/// Respawn rules offered in the settings; config files can set others.
+const RESPAWN_CHOICES: [Respawn; 7] = [
+    Respawn::Off,
+    Respawn::EveryTurns(3),
+    Respawn::EveryTurns(5),
+    Respawn::EveryTurns(10),
+    Respawn::Chance(0.1),
+    Respawn::Chance(0.25),
+    Respawn::Chance(0.5),
+];
+
+fn respawn_label(respawn: Respawn) -> String {
+    match respawn {
+        Respawn::Off => "OFF".to_string(),
+        Respawn::EveryTurns(1) => "Every turn".to_string(),
+        Respawn::EveryTurns(turns) => format!("Every {} turns", turns),
+        Respawn::Chance(chance) => format!("{}% a turn", (chance * 100.0).round()),
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct Settings {
+    pub(super) game: GameSettings,
+    pub(super) seats: [Seat; 2],
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            game: GameSettings::default(),
+            seats: [Seat::Human; 2],
+        }
+    }
+}
+
+impl Settings {
+    pub(super) fn seat(&self, player: char) -> Seat {
+        self.seats[player_index(player)]
+    }
+}
+
+/// Copy of the settings and preferences being edited on the settings screen.
+/// It only replaces them once it validates and the player confirms.
+#[derive(Resource, Default)]
+pub(super) struct SettingsDraft {
+    settings: Settings,
+    preferences: Preferences,
+}
+
+impl SettingsDraft {
+    pub(super) fn new(settings: &Settings, preferences: &Preferences) -> Self {
+        Self {
+            settings: settings.clone(),
+            preferences: preferences.clone(),
+        }
+    }
+
+    /// Takes on a window change made outside the settings screen.
+    pub(super) fn set_window(&mut self, window: &WindowConfig) {
+        self.preferences.window = window.clone();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingField {
+    Rows,
+    Cols,
+    WinLength,
+    PowerUps,
+    PowerUpCount,
+    PowerUpType(char),
+    Placement,
+    Spacing,
+    Respawn,
+    MaxPowerUps,
+    Expansion,
+    Gravity,
+    ChainReactions,
+    Inventory,
+    FirstPlayer,
+    Seat(usize),
+    Theme,
+    Skin,
+    DisplayMode,
+    Resolution,
+    VSync,
+    AnimationSpeed,
+    ReducedMotion,
+    Volume,
+    EffectsVolume,
+    Music,
+    MusicVolume,
+    Mute,
+}
+
+impl SettingField {
+    /// Fields for the game rules, shown in the left column.
+    fn game() -> impl Iterator<Item = SettingField> {
+        [
+            SettingField::Rows,
+            SettingField::Cols,
+            SettingField::WinLength,
+            SettingField::PowerUps,
+            SettingField::PowerUpCount,
+        ]
+        .into_iter()
+        .chain(
+            power_ups::registry()
+                .glyphs()
+                .into_iter()
+                .map(SettingField::PowerUpType),
+        )
+        .chain([
+            SettingField::Placement,
+            SettingField::Spacing,
+            SettingField::Respawn,
+            SettingField::MaxPowerUps,
+            SettingField::Expansion,
+            SettingField::Gravity,
+            SettingField::ChainReactions,
+            SettingField::Inventory,
+        ])
+    }
+
+    /// Fields for the players, looks and sound, shown in the right column.
+    fn presentation() -> impl Iterator<Item = SettingField> {
+        [
+            SettingField::FirstPlayer,
+            SettingField::Seat(0),
+            SettingField::Seat(1),
+            SettingField::Theme,
+            SettingField::Skin,
+            SettingField::DisplayMode,
+            SettingField::Resolution,
+            SettingField::VSync,
+            SettingField::AnimationSpeed,
+            SettingField::ReducedMotion,
+            SettingField::Volume,
+            SettingField::EffectsVolume,
+            SettingField::Music,
+            SettingField::MusicVolume,
+            SettingField::Mute,
+        ]
+        .into_iter()
+    }
+
+    fn label(&self) -> String {
+        match self {
+            SettingField::Rows => "Rows".to_string(),
+            SettingField::Cols => "Columns".to_string(),
+            SettingField::WinLength => "Pieces to connect".to_string(),
+            SettingField::PowerUps => "Power-ups".to_string(),
+            SettingField::PowerUpCount => "Initial power-ups".to_string(),
+            SettingField::PowerUpType(symbol) => match power_ups::registry().get(*symbol) {
+                Some(power_up) => format!("{} power-up", power_up.name()),
+                None => format!("'{}' power-up", symbol),
+            },
+            SettingField::Placement => "Power-up placement".to_string(),
+            SettingField::Spacing => "Power-up spacing".to_string(),
+            SettingField::Respawn => "Respawn power-ups".to_string(),
+            SettingField::MaxPowerUps => "Most power-ups at once".to_string(),
+            SettingField::Expansion => "Expand board on a tie".to_string(),
+            SettingField::Gravity => "Pieces fall into gaps".to_string(),
+            SettingField::ChainReactions => "Chain reactions".to_string(),
+            SettingField::Inventory => "Collect power-ups".to_string(),
+            SettingField::FirstPlayer => "First player".to_string(),
+            SettingField::Seat(seat) => format!("Player {}", seat + 1),
+            SettingField::Theme => "Theme".to_string(),
+            SettingField::Skin => "Board skin".to_string(),
+            SettingField::DisplayMode => "Display mode".to_string(),
+            SettingField::Resolution => "Resolution".to_string(),
+            SettingField::VSync => "VSync".to_string(),
+            SettingField::AnimationSpeed => "Animation speed".to_string(),
+            SettingField::ReducedMotion => "Reduced motion".to_string(),
+            SettingField::Volume => "Master volume".to_string(),
+            SettingField::EffectsVolume => "Effects volume".to_string(),
+            SettingField::Music => "Music".to_string(),
+            SettingField::MusicVolume => "Music volume".to_string(),
+            SettingField::Mute => "Mute".to_string(),
+        }
+    }
+
+    fn value(&self, draft: &SettingsDraft) -> String {
+        let settings = &draft.settings;
+        let preferences = &draft.preferences;
+        let on_off = |on: bool| if on { "ON" } else { "OFF" }.to_string();
+        let percent = |level: f32| format!("{}%", (level * 100.0).round());
+        let game = &settings.game;
+        match self {
+            SettingField::Rows => game.rows.to_string(),
+            SettingField::Cols => game.cols.to_string(),
+            SettingField::WinLength => game.win_length.to_string(),
+            SettingField::PowerUps => on_off(game.power_ups),
+            SettingField::PowerUpCount => game.power_up_count.to_string(),
+            SettingField::PowerUpType(symbol) => on_off(game.enabled_power_ups.contains(symbol)),
+            SettingField::Placement => {
+                placement_label(game.placement.mirrored, game.placement.parity_balanced)
+            }
+            SettingField::Spacing => game.placement.min_distance.to_string(),
+            SettingField::Respawn => respawn_label(game.respawn),
+            SettingField::MaxPowerUps => game.max_power_ups.to_string(),
+            SettingField::Expansion => on_off(game.expansion),
+            SettingField::Gravity => on_off(game.gravity),
+            SettingField::ChainReactions => on_off(game.chain_reactions),
+            SettingField::Inventory => on_off(game.inventory),
+            SettingField::FirstPlayer => {
+                format!("Player {}", player_index(game.first_player) + 1)
+            }
+            SettingField::Seat(seat) => settings.seats[*seat].label(),
+            SettingField::Theme => preferences.theme.clone(),
+            SettingField::Skin => preferences.skin.clone(),
+            SettingField::DisplayMode => preferences.window.mode.label().to_string(),
+            SettingField::Resolution => preferences.window.resolution_label(),
+            SettingField::VSync => on_off(preferences.window.vsync),
+            SettingField::AnimationSpeed => format!("{}x", preferences.animation_speed),
+            SettingField::ReducedMotion => on_off(preferences.reduced_motion),
+            SettingField::Volume => percent(preferences.volume),
+            SettingField::EffectsVolume => percent(preferences.effects_volume),
+            SettingField::Music => on_off(preferences.music),
+            SettingField::MusicVolume => percent(preferences.music_volume),
+            SettingField::Mute => on_off(preferences.muted),
+        }
+    }
+
+    /// Moves the setting one step; toggles ignore the direction.
+    fn adjust(&self, draft: &mut SettingsDraft, themes: &Themes, skins: &Skins, step: isize) {
+        let settings = &mut draft.settings;
+        let preferences = &mut draft.preferences;
+        // Volumes move in steps of 10%, rounded so repeated steps don't drift.
+        let nudge_level =
+            |level: f32| ((level * 10.0).round() + step as f32).clamp(0.0, 10.0) / 10.0;
+        let nudge = |value: usize, min: usize, max: usize| {
+            (value as isize + step).clamp(min as isize, max as isize) as usize
+        };
+        let game = &mut settings.game;
+        match self {
+            SettingField::Rows => game.rows = nudge(game.rows, MIN_BOARD_SIZE, MAX_BOARD_SIZE),
+            SettingField::Cols => game.cols = nudge(game.cols, MIN_BOARD_SIZE, MAX_BOARD_SIZE),
+            SettingField::WinLength => {
+                game.win_length = nudge(game.win_length, MIN_WIN_LENGTH, MAX_BOARD_SIZE)
+            }
+            SettingField::PowerUps => game.power_ups = !game.power_ups,
+            SettingField::PowerUpCount => {
+                game.power_up_count = nudge(game.power_up_count, 0, MAX_BOARD_SIZE * MAX_BOARD_SIZE)
+            }
+            SettingField::PowerUpType(symbol) => {
+                if let Some(index) = game.enabled_power_ups.iter().position(|s| s == symbol) {
+                    game.enabled_power_ups.remove(index);
+                } else {
+                    game.enabled_power_ups.push(*symbol);
+                }
+            }
+            SettingField::Placement => {
+                let placement = &mut game.placement;
+                let current = PLACEMENT_CHOICES
+                    .iter()
+                    .position(|&choice| choice == (placement.mirrored, placement.parity_balanced))
+                    .unwrap_or(0) as isize;
+                let next = (current + step).rem_euclid(PLACEMENT_CHOICES.len() as isize);
+                (placement.mirrored, placement.parity_balanced) = PLACEMENT_CHOICES[next as usize];
+            }
+            SettingField::Spacing => {
+                game.placement.min_distance = nudge(game.placement.min_distance, 1, 4)
+            }
+            SettingField::Respawn => {
+                let current = RESPAWN_CHOICES
+                    .iter()
+                    .position(|choice| *choice == game.respawn)
+                    .unwrap_or(0) as isize;
+                let next = (current + step).rem_euclid(RESPAWN_CHOICES.len() as isize);
+                game.respawn = RESPAWN_CHOICES[next as usize];
+            }
+            SettingField::MaxPowerUps => {
+                game.max_power_ups = nudge(game.max_power_ups, 1, MAX_BOARD_SIZE * MAX_BOARD_SIZE)
+            }
+            SettingField::Expansion => game.expansion = !game.expansion,
+            SettingField::Gravity => game.gravity = !game.gravity,
+            SettingField::ChainReactions => game.chain_reactions = !game.chain_reactions,
+            SettingField::Inventory => game.inventory = !game.inventory,
+            SettingField::FirstPlayer => {
+                game.first_player = if game.first_player == PLAYER_X {
+                    PLAYER_O
+                } else {
+                    PLAYER_X
+                };
+            }
+            SettingField::Seat(seat) => {
+                let current = Seat::ALL
+                    .iter()
+                    .position(|s| *s == settings.seats[*seat])
+                    .unwrap_or(0) as isize;
+                let next = (current + step).rem_euclid(Seat::ALL.len() as isize);
+                settings.seats[*seat] = Seat::ALL[next as usize];
+            }
+            SettingField::Theme => {
+                preferences.theme = cycle(themes.names(), &preferences.theme, step)
+            }
+            SettingField::Skin => preferences.skin = cycle(skins.names(), &preferences.skin, step),
+            SettingField::DisplayMode => {
+                preferences.window.mode = preferences.window.mode.step(step)
+            }
+            SettingField::Resolution => preferences.window.step_resolution(step),
+            SettingField::VSync => preferences.window.vsync = !preferences.window.vsync,
+            SettingField::AnimationSpeed => {
+                preferences.animation_speed =
+                    ((preferences.animation_speed * 4.0).round() + step as f32).clamp(1.0, 12.0)
+                        / 4.0
+            }
+            SettingField::ReducedMotion => preferences.reduced_motion = !preferences.reduced_motion,
+            SettingField::Volume => preferences.volume = nudge_level(preferences.volume),
+            SettingField::EffectsVolume => {
+                preferences.effects_volume = nudge_level(preferences.effects_volume)
+            }
+            SettingField::Music => preferences.music = !preferences.music,
+            SettingField::MusicVolume => {
+                preferences.music_volume = nudge_level(preferences.music_volume)
+            }
+            SettingField::Mute => preferences.muted = !preferences.muted,
+        }
+    }
+}
+
+/// The name `step` places after `current`, wrapping around.
+fn cycle<'a>(names: impl Iterator<Item = &'a str>, current: &str, step: isize) -> String {
+    let names: Vec<&str> = names.collect();
+    let current = names.iter().position(|name| *name == current).unwrap_or(0) as isize;
+    let next = (current + step).rem_euclid(names.len() as isize);
+    names[next as usize].to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsAction {
+    Controls,
+    Done,
+    Cancel,
+}
+
+#[derive(Component)]
+pub(super) struct SettingsUI;
+
+#[derive(Component)]
+pub(super) struct SettingButton {
+    field: SettingField,
+    step: isize,
+}
+
+#[derive(Component)]
+pub(super) struct SettingValue(SettingField);
+
+#[derive(Component)]
+pub(super) struct SettingsError;
+
+#[derive(Component)]
+pub(super) struct SettingsMenuButton(SettingsAction);
+
+fn spawn_step_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    field: SettingField,
+    step: isize,
+    theme: &Theme,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(ROW_HEIGHT),
+                    height: Val::Px(ROW_HEIGHT - 4.0),
+                    margin: UiRect::all(Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.secondary_button.into(),
+                ..default()
+            },
+            SettingButton { field, step },
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                if step < 0 { "<" } else { ">" },
+                TextStyle {
+                    font: theme.bold_font(asset_server),
+                    font_size: 18.0,
+                    color: theme.text,
+                },
+            ));
+        });
+}
+
+/// One line of the settings screen: label, value and the buttons to change it.
+fn spawn_setting_row(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    theme: &Theme,
+    draft: &SettingsDraft,
+    field: SettingField,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|line| {
+            spawn_label(line, asset_server, theme, &field.label(), 200.0);
+            spawn_step_button(line, asset_server, field, -1, theme);
+            line.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(160.0),
+                    height: Val::Px(ROW_HEIGHT),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|cell| {
+                cell.spawn((
+                    TextBundle::from_section(
+                        field.value(draft),
+                        TextStyle {
+                            font: theme.bold_font(asset_server),
+                            font_size: 20.0,
+                            color: theme.text,
+                        },
+                    ),
+                    SettingValue(field),
+                ));
+            });
+            spawn_step_button(line, asset_server, field, 1, theme);
+        });
+}
+
+pub(super) fn setup_settings_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    draft: Res<SettingsDraft>,
+    theme: Res<Theme>,
+) {
+    let row = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+    let column = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexStart,
+            margin: UiRect::horizontal(Val::Px(20.0)),
+            ..default()
+        },
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.menu_background.into(),
+                ..default()
+            },
+            SettingsUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Settings",
+                    TextStyle {
+                        font: theme.bold_font(&asset_server),
+                        font_size: 50.0,
+                        color: theme.title,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+            );
+
+            parent.spawn(
+                TextBundle::from_section(
+                    "Board and rule changes apply from the next game.",
+                    TextStyle {
+                        font: theme.regular_font(&asset_server),
+                        font_size: 20.0,
+                        color: theme.text,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(15.0)),
+                    ..default()
+                }),
+            );
+
+            parent.spawn(row()).with_children(|columns| {
+                for fields in [
+                    SettingField::game().collect::<Vec<_>>(),
+                    SettingField::presentation().collect(),
+                ] {
+                    columns.spawn(column()).with_children(|parent| {
+                        for field in fields {
+                            spawn_setting_row(parent, &asset_server, &theme, &draft, field);
+                        }
+                    });
+                }
+            });
+
+            parent.spawn((
+                TextBundle::from_section(
+                    draft.settings.game.validate().err().unwrap_or_default(),
+                    TextStyle {
+                        font: theme.regular_font(&asset_server),
+                        font_size: 20.0,
+                        color: theme.error,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(8.0)),
+                    ..default()
+                }),
+                SettingsError,
+            ));
+
+            parent.spawn(row()).with_children(|buttons| {
+                for (label, action) in [
+                    ("Controls", SettingsAction::Controls),
+                    ("Done", SettingsAction::Done),
+                    ("Cancel", SettingsAction::Cancel),
+                ] {
+                    spawn_menu_button(
+                        buttons,
+                        &asset_server,
+                        &theme,
+                        label,
+                        SettingsMenuButton(action),
+                    );
+                }
+            });
+        });
+}
+
+pub(super) fn setting_button_system(
+    mut interaction_query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &mut BackgroundColor,
+        &SettingButton,
+    )>,
+    focus: Res<MenuFocus>,
+    mut draft: ResMut<SettingsDraft>,
+    themes: Res<Themes>,
+    skins: Res<Skins>,
+    theme: Res<Theme>,
+) {
+    for (entity, interaction, mut background_color, button) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(entity, interaction) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Pressed => {
+                button
+                    .field
+                    .adjust(&mut draft, &themes, &skins, button.step);
+            }
+            Interaction::Hovered => {
+                *background_color = theme.secondary_button_hover.into();
+            }
+            Interaction::None => {
+                *background_color = theme.secondary_button.into();
+            }
+        }
+    }
+}
+
+pub(super) fn update_setting_labels(
+    draft: Res<SettingsDraft>,
+    mut value_query: Query<(&SettingValue, &mut Text), Without<SettingsError>>,
+    mut error_query: Query<&mut Text, With<SettingsError>>,
+) {
+    if !draft.is_changed() {
+        return;
+    }
+
+    for (value, mut text) in &mut value_query {
+        text.sections[0].value = value.0.value(&draft);
+    }
+    for mut text in &mut error_query {
+        text.sections[0].value = draft.settings.game.validate().err().unwrap_or_default();
+    }
+}
+
+pub(super) fn settings_menu_button_system(
+    mut interaction_query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &mut BackgroundColor,
+        &SettingsMenuButton,
+    )>,
+    focus: Res<MenuFocus>,
+    draft: Res<SettingsDraft>,
+    mut settings: ResMut<Settings>,
+    game_state: Res<GameStateResource>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut theme: ResMut<Theme>,
+    themes: Res<Themes>,
+    mut preferences: ResMut<Preferences>,
+) {
+    // Return to the paused game if we were opened from it.
+    let back = if game_state.resume {
+        AppState::InGame
+    } else {
+        AppState::MainMenu
+    };
+
+    for (entity, interaction, mut background_color, button) in &mut interaction_query {
+        let Some(interaction) = focus.interaction(entity, interaction) else {
+            continue;
+        };
+        match interaction {
+            Interaction::Pressed => {
+                *background_color = theme.button_pressed.into();
+            }
+            Interaction::Hovered => {
+                *background_color = theme.button_hover.into();
+                continue;
+            }
+            Interaction::None => {
+                *background_color = theme.button.into();
+                continue;
+            }
+        }
+
+        match button.0 {
+            // The draft is kept while the controls screen is open.
+            SettingsAction::Controls => app_state.set(AppState::Controls),
+            SettingsAction::Done => {
+                // Invalid settings stay on screen with the error shown.
+                if draft.settings.game.validate().is_ok() {
+                    if *settings != draft.settings {
+                        *settings = draft.settings.clone();
+                    }
+                    if preferences.theme != draft.preferences.theme {
+                        *theme = themes.get(&draft.preferences.theme);
+                    }
+                    if *preferences != draft.preferences {
+                        *preferences = draft.preferences.clone();
+                    }
+                    app_state.set(back.clone());
+                }
+            }
+            SettingsAction::Cancel => app_state.set(back.clone()),
+        }
+    }
+}
+
+pub(super) fn cleanup_settings_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<SettingsUI>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,265 @@
+use super::config::Preferences;
+use super::storage;
+use crate::game::{PLAYER_O, PLAYER_X};
+use crate::power_ups;
+use bevy::asset::FileAssetIo;
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Skins shipped with the game. User skins with the same name replace them.
+const BUILT_IN_SKINS: [&str; 2] = [
+    include_str!("../../assets/skins/flat.ron"),
+    include_str!("../../assets/skins/textured.ron"),
+];
+
+/// Folder in the config directory searched for extra `.ron` skin files.
+const SKINS_DIR: &str = "skins";
+
+/// Folder Bevy loads assets from, under its base path.
+const ASSET_FOLDER: &str = "assets";
+
+/// How one kind of board element is drawn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(super) enum SkinPart {
+    /// A circle or square in the theme's colors.
+    #[default]
+    Flat,
+    /// A power-up's own icon, from its definition. Anything without one is
+    /// drawn flat.
+    Icon,
+    /// A whole image from the assets folder.
+    Texture(String),
+    /// Tile `index` of an image cut into a `columns` x `rows` grid of
+    /// `tile_size` pixel tiles, counting left to right, top to bottom.
+    Atlas {
+        path: String,
+        tile_size: Vec2,
+        columns: usize,
+        rows: usize,
+        index: usize,
+    },
+}
+
+/// Which image, if any, each board element is drawn with. Anything left out
+/// of a skin file is drawn flat.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct Skin {
+    pub(super) name: String,
+    /// Pieces for Player 1 and Player 2.
+    pub(super) pieces: (SkinPart, SkinPart),
+    pub(super) cell: SkinPart,
+    pub(super) obstacle: SkinPart,
+    /// Power-ups by id.
+    pub(super) power_ups: HashMap<String, SkinPart>,
+    /// Power-ups not named in `power_ups`.
+    pub(super) other_power_ups: SkinPart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum SkinElement {
+    Piece(char),
+    Cell,
+    Obstacle,
+    /// A power-up, by id.
+    PowerUp(&'static str),
+}
+
+impl Skin {
+    fn parts(&self) -> Vec<(SkinElement, SkinPart)> {
+        let mut parts = vec![
+            (SkinElement::Piece(PLAYER_X), self.pieces.0.clone()),
+            (SkinElement::Piece(PLAYER_O), self.pieces.1.clone()),
+            (SkinElement::Cell, self.cell.clone()),
+            (SkinElement::Obstacle, self.obstacle.clone()),
+        ];
+        for power_up in power_ups::registry().iter() {
+            let part = self
+                .power_ups
+                .get(power_up.id())
+                .unwrap_or(&self.other_power_ups);
+            let part = match part {
+                SkinPart::Icon => power_up
+                    .visual()
+                    .icon
+                    .clone()
+                    .map_or(SkinPart::Flat, SkinPart::Texture),
+                part => part.clone(),
+            };
+            parts.push((SkinElement::PowerUp(power_up.id()), part));
+        }
+        parts
+    }
+}
+
+/// Every skin that can be picked in the settings.
+#[derive(Resource)]
+pub(super) struct Skins(Vec<Skin>);
+
+impl Skins {
+    /// The built-in skins plus any skin files in the config directory.
+    pub(super) fn load() -> Self {
+        let mut skins: Vec<Skin> = BUILT_IN_SKINS
+            .iter()
+            .map(|source| ron::from_str(source).expect("built-in skin should parse"))
+            .collect();
+
+        for skin in storage::load_ron_dir::<Skin>(SKINS_DIR) {
+            match skins.iter_mut().find(|s| s.name == skin.name) {
+                Some(existing) => *existing = skin,
+                None => skins.push(skin),
+            }
+        }
+        Self(skins)
+    }
+
+    pub(super) fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|skin| skin.name.as_str())
+    }
+
+    fn get(&self, name: &str) -> &Skin {
+        self.0
+            .iter()
+            .find(|skin| skin.name == name)
+            .unwrap_or_else(|| {
+                eprintln!("Unknown skin '{}', using '{}'.", name, self.0[0].name);
+                &self.0[0]
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SkinTexture {
+    Image(Handle<Image>),
+    Atlas(Handle<TextureAtlas>, usize),
+}
+
+/// The selected skin with its textures loaded. Elements without a usable
+/// texture are drawn flat.
+#[derive(Resource, Default)]
+pub(super) struct LoadedSkin {
+    name: String,
+    textures: HashMap<SkinElement, SkinTexture>,
+}
+
+impl LoadedSkin {
+    pub(super) fn is_textured(&self, element: SkinElement) -> bool {
+        self.textures.contains_key(&element)
+    }
+
+    /// Spawns `element` as a `size` wide sprite at `transform`, or the `flat`
+    /// bundle when the skin draws it flat.
+    pub(super) fn spawn<'w, 's, 'a, B: Bundle>(
+        &self,
+        commands: &'a mut Commands<'w, 's>,
+        element: SkinElement,
+        size: f32,
+        transform: Transform,
+        flat: impl FnOnce() -> B,
+    ) -> EntityCommands<'w, 's, 'a> {
+        let custom_size = Some(Vec2::splat(size));
+        match self.textures.get(&element) {
+            Some(SkinTexture::Image(texture)) => commands.spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size,
+                    ..default()
+                },
+                texture: texture.clone(),
+                transform,
+                ..default()
+            }),
+            Some(SkinTexture::Atlas(atlas, index)) => commands.spawn(SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    index: *index,
+                    custom_size,
+                    ..default()
+                },
+                texture_atlas: atlas.clone(),
+                transform,
+                ..default()
+            }),
+            None => commands.spawn(flat()),
+        }
+    }
+}
+
+/// Whether `path` exists in the assets folder, reporting it if not.
+fn texture_exists(path: &str) -> bool {
+    let exists = FileAssetIo::get_base_path()
+        .join(ASSET_FOLDER)
+        .join(path)
+        .exists();
+    if !exists {
+        eprintln!("Skin texture '{}' not found, drawing it flat.", path);
+    }
+    exists
+}
+
+fn load_texture(
+    part: &SkinPart,
+    asset_server: &AssetServer,
+    atlases: &mut Assets<TextureAtlas>,
+) -> Option<SkinTexture> {
+    match part {
+        SkinPart::Flat | SkinPart::Icon => None,
+        SkinPart::Texture(path) => {
+            texture_exists(path).then(|| SkinTexture::Image(asset_server.load(path.as_str())))
+        }
+        SkinPart::Atlas {
+            path,
+            tile_size,
+            columns,
+            rows,
+            index,
+        } => {
+            if *index >= columns * rows {
+                eprintln!(
+                    "Skin atlas '{}' has no tile {}, drawing it flat.",
+                    path, index
+                );
+                return None;
+            }
+            texture_exists(path).then(|| {
+                let atlas = TextureAtlas::from_grid(
+                    asset_server.load(path.as_str()),
+                    *tile_size,
+                    *columns,
+                    *rows,
+                    None,
+                    None,
+                );
+                SkinTexture::Atlas(atlases.add(atlas), *index)
+            })
+        }
+    }
+}
+
+/// Loads the textures of the skin chosen in the preferences whenever it
+/// changes.
+pub(super) fn apply_skin(
+    preferences: Res<Preferences>,
+    skins: Res<Skins>,
+    mut loaded: ResMut<LoadedSkin>,
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    if loaded.name == preferences.skin {
+        return;
+    }
+
+    let skin = skins.get(&preferences.skin);
+    let textures = skin
+        .parts()
+        .into_iter()
+        .filter_map(|(element, part)| {
+            load_texture(&part, &asset_server, &mut atlases).map(|texture| (element, texture))
+        })
+        .collect();
+
+    *loaded = LoadedSkin {
+        name: preferences.skin.clone(),
+        textures,
+    };
+}
@@ -0,0 +1,73 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "rusty-connect-four";
+
+/// Location of a file in the platform config directory, falling back to the
+/// working directory on platforms without one.
+pub(super) fn config_path(file_name: &str) -> PathBuf {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .unwrap_or_default()
+        .join(file_name)
+}
+
+/// Reads a RON file. A missing file is `Ok(None)`; a file that can't be read
+/// or parsed is an error describing what went wrong.
+pub(super) fn load_ron<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>, String> {
+    read_ron(&config_path(file_name))
+}
+
+pub(super) fn save_ron<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    write_ron(&config_path(file_name), value)
+}
+
+/// Like [`load_ron`], for a file anywhere on disk.
+pub(super) fn read_ron<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents =
+        fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    ron::from_str(&contents)
+        .map(Some)
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Reads every `.ron` file in a folder of the config directory, in file name
+/// order. Files that can't be read are reported and skipped.
+pub(super) fn load_ron_dir<T: DeserializeOwned>(dir_name: &str) -> Vec<T> {
+    let Ok(entries) = fs::read_dir(config_path(dir_name)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| match read_ron(path) {
+            Ok(value) => value,
+            Err(err) => {
+                eprintln!("Could not load {}", err);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Like [`save_ron`], for a file anywhere on disk.
+pub(super) fn write_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+    }
+
+    let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())?;
+    fs::write(path, contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
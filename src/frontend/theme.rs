@@ -0,0 +1,126 @@
+use super::{player_index, storage};
+use crate::power_ups::PowerUp;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Themes shipped with the game. User themes with the same name replace them.
+const BUILT_IN_THEMES: [&str; 3] = [
+    include_str!("../../assets/themes/classic.ron"),
+    include_str!("../../assets/themes/dark.ron"),
+    include_str!("../../assets/themes/high-contrast.ron"),
+];
+
+/// Folder in the config directory searched for extra `.ron` theme files.
+const THEMES_DIR: &str = "themes";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ThemeFonts {
+    pub(super) regular: String,
+    pub(super) bold: String,
+}
+
+/// Colors and fonts used by every screen.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Theme {
+    pub(super) name: String,
+    /// Window clear color behind everything.
+    pub(super) background: Color,
+    pub(super) menu_background: Color,
+    /// Dimming layer behind the pause menu.
+    pub(super) overlay: Color,
+    pub(super) board_backdrop: Color,
+    pub(super) board: Color,
+    pub(super) cell: Color,
+    /// Piece colors for Player 1 and Player 2.
+    pub(super) players: [Color; 2],
+    /// Column number colors, left to right. Extra columns use `title`.
+    pub(super) column_labels: Vec<Color>,
+    /// Power-up colors by id. Power-ups left out use their own.
+    pub(super) power_ups: HashMap<String, Color>,
+    pub(super) obstacle: Color,
+    pub(super) text: Color,
+    pub(super) title: Color,
+    pub(super) error: Color,
+    pub(super) button: Color,
+    pub(super) button_hover: Color,
+    pub(super) button_pressed: Color,
+    pub(super) secondary_button: Color,
+    pub(super) secondary_button_hover: Color,
+    /// Button waiting for input, e.g. a key binding being changed.
+    pub(super) button_active: Color,
+    pub(super) fonts: ThemeFonts,
+}
+
+impl Theme {
+    pub(super) fn player_color(&self, player: char) -> Color {
+        self.players[player_index(player)]
+    }
+
+    /// The theme's color for a power-up, or the power-up's own.
+    pub(super) fn power_up_color(&self, power_up: &dyn PowerUp) -> Color {
+        self.power_ups
+            .get(power_up.id())
+            .copied()
+            .unwrap_or_else(|| {
+                let [red, green, blue] = power_up.visual().color;
+                Color::rgb(red, green, blue)
+            })
+    }
+
+    pub(super) fn column_label_color(&self, col: usize) -> Color {
+        self.column_labels.get(col).copied().unwrap_or(self.title)
+    }
+
+    pub(super) fn regular_font(&self, asset_server: &AssetServer) -> Handle<Font> {
+        asset_server.load(self.fonts.regular.as_str())
+    }
+
+    pub(super) fn bold_font(&self, asset_server: &AssetServer) -> Handle<Font> {
+        asset_server.load(self.fonts.bold.as_str())
+    }
+}
+
+/// Every theme that can be picked in the settings.
+#[derive(Resource)]
+pub(super) struct Themes(Vec<Theme>);
+
+impl Themes {
+    /// The built-in themes plus any theme files in the config directory.
+    /// Theme files that can't be read are reported and skipped.
+    pub(super) fn load() -> Self {
+        let mut themes: Vec<Theme> = BUILT_IN_THEMES
+            .iter()
+            .map(|source| ron::from_str(source).expect("built-in theme should parse"))
+            .collect();
+
+        for theme in storage::load_ron_dir::<Theme>(THEMES_DIR) {
+            match themes.iter_mut().find(|t| t.name == theme.name) {
+                Some(existing) => *existing = theme,
+                None => themes.push(theme),
+            }
+        }
+        Self(themes)
+    }
+
+    pub(super) fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|theme| theme.name.as_str())
+    }
+
+    /// The theme called `name`, or the first built-in theme if there is none.
+    pub(super) fn get(&self, name: &str) -> Theme {
+        match self.0.iter().find(|theme| theme.name == name) {
+            Some(theme) => theme.clone(),
+            None => {
+                eprintln!("Unknown theme '{}', using '{}'.", name, self.0[0].name);
+                self.0[0].clone()
+            }
+        }
+    }
+}
+
+pub(super) fn apply_theme(theme: Res<Theme>, mut clear_color: ResMut<ClearColor>) {
+    if theme.is_changed() {
+        clear_color.0 = theme.background;
+    }
+}
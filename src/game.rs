@@ -1,5 +1,11 @@
-use rand::random;
+use crate::mods;
+use crate::placement::Placement;
+use crate::power_ups::{self, Activation, Effect, PowerUp};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::io::{self, Write};
+use std::sync::{Mutex, PoisonError};
 
 pub const ROWS: usize = 6;
 pub const COLS: usize = 7;
@@ -10,73 +16,447 @@ pub const EXPANDED_ROWS: usize = 10;
 pub const EXPANDED_COLS: usize = 10;
 pub const OBSTACLE: char = '#';
 
+pub const MIN_BOARD_SIZE: usize = 4;
+pub const MAX_BOARD_SIZE: usize = 12;
+pub const MIN_WIN_LENGTH: usize = 3;
+/// How many steps a chain reaction can spread from the first power-up.
+pub const MAX_CHAIN_DEPTH: usize = 8;
+/// Layouts tried before settling for the fairest one found.
+const PLACEMENT_ATTEMPTS: usize = 20;
+
+/// When new power-ups appear while a game is played.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Respawn {
+    #[default]
+    Off,
+    /// One after every this many turns.
+    EveryTurns(u32),
+    /// One after each turn with this chance, from 0.0 to 1.0.
+    Chance(f32),
+}
+
+/// Rules chosen before a game starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub rows: usize,
+    pub cols: usize,
+    pub win_length: usize,
+    pub power_ups: bool,
+    pub power_up_count: usize,
+    pub enabled_power_ups: Vec<char>,
+    pub expansion: bool,
+    /// Pieces left hanging by a power-up fall into the gaps below them.
+    pub gravity: bool,
+    /// Power-ups hit by another power-up's effect go off too.
+    pub chain_reactions: bool,
+    /// Landing on a power-up collects it to use on a later turn instead of
+    /// setting it off.
+    pub inventory: bool,
+    pub respawn: Respawn,
+    /// Respawning stops while this many power-ups are on the board.
+    pub max_power_ups: usize,
+    pub placement: Placement,
+    pub first_player: char,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            rows: ROWS,
+            cols: COLS,
+            win_length: 4,
+            power_ups: false,
+            power_up_count: 6,
+            enabled_power_ups: power_ups::registry().glyphs(),
+            expansion: true,
+            gravity: false,
+            chain_reactions: false,
+            inventory: false,
+            respawn: Respawn::Off,
+            max_power_ups: 6,
+            placement: Placement::default(),
+            first_player: PLAYER_X,
+        }
+    }
+}
+
+impl GameSettings {
+    /// Checks that the settings make a playable game together.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, size) in [("rows", self.rows), ("columns", self.cols)] {
+            if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+                return Err(format!(
+                    "The board needs {} to {} {}.",
+                    MIN_BOARD_SIZE, MAX_BOARD_SIZE, name
+                ));
+            }
+        }
+
+        if self.win_length < MIN_WIN_LENGTH || self.win_length > self.rows.max(self.cols) {
+            return Err(format!(
+                "Win length must be between {} and {} on a {}x{} board.",
+                MIN_WIN_LENGTH,
+                self.rows.max(self.cols),
+                self.rows,
+                self.cols
+            ));
+        }
+
+        if self.first_player != PLAYER_X && self.first_player != PLAYER_O {
+            return Err("The first player must be X or O.".to_string());
+        }
+
+        if self.power_ups {
+            if self.enabled_power_ups.is_empty() {
+                return Err("Enable at least one power-up type.".to_string());
+            }
+            if let Some(unknown) = self
+                .enabled_power_ups
+                .iter()
+                .find(|&&power_up| !power_ups::is_power_up(power_up))
+            {
+                return Err(format!("Unknown power-up type '{}'.", unknown));
+            }
+            if self.power_up_count >= self.rows * self.cols {
+                return Err(format!(
+                    "{} power-ups do not fit on a {}x{} board.",
+                    self.power_up_count, self.rows, self.cols
+                ));
+            }
+            match self.respawn {
+                Respawn::Off => {}
+                Respawn::EveryTurns(0) => {
+                    return Err("Power-ups can respawn every 1 turn at most.".to_string());
+                }
+                Respawn::EveryTurns(_) => {}
+                Respawn::Chance(chance) if !(chance > 0.0 && chance <= 1.0) => {
+                    return Err("The respawn chance must be above 0% and at most 100%.".to_string());
+                }
+                Respawn::Chance(_) => {}
+            }
+            self.placement.validate()?;
+            let registry = power_ups::registry();
+            if self
+                .enabled_power_ups
+                .iter()
+                .filter_map(|&glyph| registry.get(glyph))
+                .all(|power_up| self.placement.weight(power_up) == 0)
+            {
+                return Err("Give an enabled power-up a weight above 0.".to_string());
+            }
+            if self.respawn != Respawn::Off && self.max_power_ups == 0 {
+                return Err("Allow at least one power-up on the board to respawn.".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Board size after a tie expands it, growing by as much as the classic
+    /// 6x7 board grows to 10x10.
+    pub fn expanded_size(&self) -> (usize, usize) {
+        (
+            self.rows + EXPANDED_ROWS - ROWS,
+            self.cols + EXPANDED_COLS - COLS,
+        )
+    }
+}
+
+/// Position of `player`'s entries in per-player lists: X first, then O.
+pub fn player_index(player: char) -> usize {
+    if player == PLAYER_X {
+        0
+    } else {
+        1
+    }
+}
+
+/// Where a dropped piece ended up, and the power-ups it set off in the order
+/// they went off, or the one it collected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Landing {
+    pub row: usize,
+    pub col: usize,
+    pub power_ups: Vec<Activation>,
+    pub collected: Option<char>,
+}
+
+/// A power-up waiting for the player who set it off to pick the opponent's
+/// piece it destroys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Targeting {
+    pub row: usize,
+    pub col: usize,
+    pub glyph: char,
+    /// How many cells away, in any direction, the piece may be.
+    pub radius: usize,
+    /// Whether it went off as a piece was dropped, so the turn passes on
+    /// once it is resolved.
+    pub ends_turn: bool,
+}
+
+/// The mods' verdict on the last position checked, so the scripts run once
+/// however often a search asks. Copies start out empty.
+#[derive(Default)]
+struct ModVerdict(Mutex<Option<Verdict>>);
+
+struct Verdict {
+    board: Vec<Vec<char>>,
+    player: char,
+    winner: Option<char>,
+}
+
+impl Clone for ModVerdict {
+    fn clone(&self) -> Self {
+        ModVerdict::default()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     board: Vec<Vec<char>>,
     current_player: char,
-    skip_turn: bool,
+    /// Turns the current player keeps before play passes on.
+    #[serde(default)]
+    skip_turns: u32,
+    /// Winner named by a mod, which ends the game whatever the board shows.
+    #[serde(default)]
+    declared_winner: Option<char>,
+    /// Power-ups each player has collected and not used yet, X's first.
+    #[serde(default)]
+    inventories: [Vec<char>; 2],
+    /// Turns finished so far, for respawning power-ups.
+    #[serde(default)]
+    turns_played: u32,
+    /// A power-up waiting for its target; nothing else can be played until
+    /// one is picked or the choice is cancelled.
+    #[serde(default)]
+    targeting: Option<Targeting>,
+    #[serde(skip)]
+    mod_verdict: ModVerdict,
     rows: usize,
     cols: usize,
     pub expanded: bool,
     pub power_ups_enabled: bool,
+    #[serde(default)]
+    settings: GameSettings,
 }
 
 impl Game {
     pub fn new(power_ups_enabled: bool) -> Game {
+        Game::with_settings(GameSettings {
+            power_ups: power_ups_enabled,
+            ..GameSettings::default()
+        })
+    }
+
+    pub fn with_settings(settings: GameSettings) -> Game {
         let mut game = Game {
-            board: vec![vec![EMPTY; COLS]; ROWS],
-            current_player: PLAYER_X,
-            skip_turn: false,
-            rows: ROWS,
-            cols: COLS,
+            board: vec![vec![EMPTY; settings.cols]; settings.rows],
+            current_player: settings.first_player,
+            skip_turns: 0,
+            declared_winner: None,
+            inventories: Default::default(),
+            turns_played: 0,
+            targeting: None,
+            mod_verdict: ModVerdict::default(),
+            rows: settings.rows,
+            cols: settings.cols,
             expanded: false,
-            power_ups_enabled,
+            power_ups_enabled: settings.power_ups,
+            settings,
         };
-        if power_ups_enabled {
-            game.initialize_power_ups(6);
+        if game.power_ups_enabled {
+            game.initialize_power_ups(game.settings.power_up_count);
         }
         game
     }
 
+    /// Whether a full board will grow instead of ending in a tie.
+    pub fn can_expand(&self) -> bool {
+        self.settings.expansion && !self.expanded
+    }
+
     pub fn initialize_power_ups(&mut self, num_power_ups: usize) {
+        let num_power_ups = num_power_ups.min(self.rows * self.cols - 1);
+        self.place_power_ups(num_power_ups, |_, _| true);
+    }
+
+    pub fn initialize_new_power_ups(&mut self, num_power_ups: usize) {
+        // New power-ups only go into the rows added by the expansion.
+        let first_new_row = self.settings.rows;
+        self.place_power_ups(num_power_ups, |row, _| row >= first_new_row);
+    }
+
+    /// Counts a finished turn and, when the respawn rules call for one,
+    /// places a new power-up, or a mirrored pair, in cells the next piece
+    /// down their column would land in. Returns where they went.
+    pub fn respawn_power_ups(&mut self) -> Vec<(usize, usize)> {
         if !self.power_ups_enabled {
-            return;
+            return Vec::new();
+        }
+        self.turns_played += 1;
+        let due = match self.settings.respawn {
+            Respawn::Off => false,
+            Respawn::EveryTurns(turns) => self.turns_played.is_multiple_of(turns),
+            Respawn::Chance(chance) => rand::random::<f32>() < chance,
+        };
+        let room = self
+            .settings
+            .max_power_ups
+            .saturating_sub(self.power_up_cells().len());
+        if !due || room == 0 {
+            return Vec::new();
         }
-        let mut placed = 0;
-        let power_up_types = ['B', 'S', 'H'];
 
-        while placed < num_power_ups {
-            let row = random::<usize>() % self.rows;
-            let col = random::<usize>() % self.cols;
+        let count = if self.settings.placement.mirrored {
+            room.min(2)
+        } else {
+            1
+        };
+        let reachable: Vec<(usize, usize)> = (0..self.cols)
+            .filter_map(|col| Some((self.landing_row(col)?, col)))
+            .collect();
+        self.place_power_ups(count, |row, col| reachable.contains(&(row, col)))
+    }
 
-            if self.board[row][col] == EMPTY {
-                let power_up = power_up_types[placed % power_up_types.len()];
-                self.board[row][col] = power_up;
-                placed += 1;
+    /// Places up to `count` enabled power-ups in empty cells that `area`
+    /// allows, picking each kind by weight, keeping it to its spawn region
+    /// and following the placement rules. Rows can't always be balanced
+    /// around mirrored pairs and spacing, so a few layouts are tried and the
+    /// one closest to the rules kept. Returns the cells they went in.
+    fn place_power_ups(
+        &mut self,
+        count: usize,
+        area: impl Fn(usize, usize) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let start = self.board.clone();
+        let mut best = (usize::MAX, start.clone(), Vec::new());
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let placed = self.try_place_power_ups(count, &area);
+            let mut misses = placed.len().abs_diff(count);
+            if self.settings.placement.parity_balanced {
+                let cells = self.power_up_cells();
+                let even = cells.iter().filter(|&&(row, _, _)| row % 2 == 0).count();
+                misses += even.abs_diff(cells.len() - even);
+            }
+            if misses < best.0 {
+                best = (misses, self.board.clone(), placed);
+            }
+            self.board.clone_from(&start);
+            if misses == 0 {
+                break;
             }
         }
+        let (_, board, placed) = best;
+        self.board = board;
+        placed
     }
 
-    pub fn initialize_new_power_ups(&mut self, num_power_ups: usize) {
+    /// One attempt at `place_power_ups`. A mirrored pair needs two places
+    /// left, so the last one can only go to the middle column.
+    fn try_place_power_ups(
+        &mut self,
+        count: usize,
+        area: impl Fn(usize, usize) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let mut placed = Vec::new();
         if !self.power_ups_enabled {
-            return;
+            return placed;
         }
-        let mut placed = 0;
-        let power_up_types = ['B', 'S', 'H'];
+        let registry = power_ups::registry();
+        let placement = &self.settings.placement;
+        let mut rng = rand::thread_rng();
+        let mut kinds: Vec<&dyn PowerUp> = self
+            .settings
+            .enabled_power_ups
+            .iter()
+            .filter_map(|&glyph| registry.get(glyph))
+            .collect();
+        let landing_rows: Vec<Option<usize>> =
+            (0..self.cols).map(|col| self.landing_row(col)).collect();
+        let mut others: Vec<(usize, usize)> = self
+            .power_up_cells()
+            .into_iter()
+            .map(|(row, col, _)| (row, col))
+            .collect();
 
-        while placed < num_power_ups {
-            let row = ROWS + (random::<usize>() % (EXPANDED_ROWS - ROWS));
-            let col = random::<usize>() % self.cols;
+        while placed.len() < count {
+            let Ok(&power_up) = kinds.choose_weighted(&mut rng, |kind| placement.weight(*kind))
+            else {
+                break;
+            };
+            let fits = |row: usize, col: usize| {
+                self.board[row][col] == EMPTY
+                    && area(row, col)
+                    && power_up.spawn_region().contains(row, col)
+                    && placement.spaced((row, col), &others)
+                    && landing_rows[col].is_some_and(|landing| row <= landing + placement.max_depth)
+            };
+            let mirror = |col: usize| self.cols - 1 - col;
+            let mut cells: Vec<(usize, usize)> = (0..self.rows)
+                .flat_map(|row| (0..self.cols).map(move |col| (row, col)))
+                .filter(|&(row, col)| fits(row, col))
+                .filter(|&(row, col)| {
+                    !placement.mirrored
+                        || mirror(col) == col
+                        || fits(row, mirror(col))
+                            && placement.spaced((row, col), &[(row, mirror(col))])
+                })
+                .collect();
 
-            if col == self.cols - 2 {
-                continue;
+            if placement.mirrored && placed.len() + 1 == count {
+                cells.retain(|&(_, col)| mirror(col) == col);
             }
 
-            if self.board[row][col] == EMPTY {
-                let power_up = power_up_types[placed % power_up_types.len()];
-                self.board[row][col] = power_up;
-                placed += 1;
+            if placement.parity_balanced {
+                // Even rows minus odd rows, and what a cell would leave it at.
+                let even = others.iter().filter(|&&(row, _)| row % 2 == 0).count() as isize;
+                let imbalance = 2 * even - others.len() as isize;
+                let remaining = (count - placed.len()) as isize;
+                let balancing: Vec<(usize, usize)> = cells
+                    .iter()
+                    .copied()
+                    .filter(|&(row, col)| {
+                        let size = if placement.mirrored && mirror(col) != col {
+                            2
+                        } else {
+                            1
+                        };
+                        let after = imbalance + if row % 2 == 0 { size } else { -size };
+                        // Closer to even, or no further off than the places
+                        // left can make up.
+                        after.abs() < imbalance.abs()
+                            || imbalance == 0 && after.abs() <= remaining - size
+                    })
+                    .collect();
+                if !balancing.is_empty() {
+                    cells = balancing;
+                }
+            }
+
+            let Some(&(row, col)) = cells.choose(&mut rng) else {
+                // No room left for this kind.
+                kinds.retain(|kind| kind.glyph() != power_up.glyph());
+                continue;
+            };
+            let mut targets = vec![(row, col)];
+            if placement.mirrored && mirror(col) != col {
+                targets.push((row, mirror(col)));
+            }
+            for (row, col) in targets {
+                self.board[row][col] = power_up.glyph();
+                placed.push((row, col));
+                others.push((row, col));
             }
         }
+        placed
+    }
+
+    /// The rules this game was started with.
+    pub fn settings(&self) -> &GameSettings {
+        &self.settings
     }
 
     pub fn get_board(&self) -> &Vec<Vec<char>> {
@@ -97,39 +477,187 @@ impl Game {
         self.current_player
     }
 
-    pub fn drop_piece(&mut self, col: usize) -> Result<(usize, usize), String> {
+    pub fn landing_row(&self, col: usize) -> Option<usize> {
         if col >= self.cols {
-            return Err("Invalid column.".to_string());
+            return None;
         }
 
-        for row in 0..self.rows {
-            if self.board[row][col] == EMPTY || ['B', 'S', 'H'].contains(&self.board[row][col]) {
-                if ['B', 'S', 'H'].contains(&self.board[row][col]) {
-                    if self.board[row][col] == 'B' {
-                        self.activate_power_up(row, col);
-                        return Ok((row, col));
-                    }
-                    self.activate_power_up(row, col);
-                }
+        (0..self.rows).find(|&row| self.is_open(row, col))
+    }
+
+    /// Whether a piece can come to rest in the cell.
+    fn is_open(&self, row: usize, col: usize) -> bool {
+        let cell = self.board[row][col];
+        cell == EMPTY || power_ups::is_power_up(cell)
+    }
+
+    pub fn drop_piece(&mut self, col: usize) -> Result<Landing, String> {
+        if self.targeting.is_some() {
+            return Err("Pick a target first.".to_string());
+        }
+        if col >= self.cols {
+            return Err("Invalid column.".to_string());
+        }
+        let Some(mut row) = self.landing_row(col) else {
+            return Err("Column is full.".to_string());
+        };
 
-                self.board[row][col] = self.current_player;
-                return Ok((row, col));
+        let glyph = self.board[row][col];
+        let collected =
+            (self.power_ups_enabled && self.settings.inventory && power_ups::is_power_up(glyph))
+                .then_some(glyph);
+        let mut power_ups = match collected {
+            Some(glyph) => {
+                self.inventories[player_index(self.current_player)].push(glyph);
+                Vec::new()
             }
+            None => self.activate_power_up(row, col, glyph),
+        };
+        if let Some(targeting) = &mut self.targeting {
+            targeting.ends_turn = true;
+        }
 
-            if self.board[row][col] == OBSTACLE {
-                if row + 1 < self.rows && self.board[row + 1][col] == EMPTY {
-                    self.board[row + 1][col] = self.current_player;
-                    return Ok((row, col));
+        // A power-up that changed its own cell took the piece with it.
+        let placed = self.board[row][col] == glyph;
+        if placed {
+            self.board[row][col] = self.current_player;
+        }
+
+        if let Some(last) = power_ups.last_mut() {
+            for fall in self.settle() {
+                match fall {
+                    // The dropped piece carries on falling as part of its drop.
+                    Effect::Fell {
+                        col: fall_col,
+                        from_row,
+                        to_row,
+                    } if placed && fall_col == col && from_row == row => row = to_row,
+                    fall => last.effects.push(fall),
                 }
             }
         }
+        Ok(Landing {
+            row,
+            col,
+            power_ups,
+            collected,
+        })
+    }
+
+    /// Power-ups `player` has collected and not used yet, oldest first.
+    pub fn inventory(&self, player: char) -> &[char] {
+        &self.inventories[player_index(player)]
+    }
+
+    /// Sets off a power-up from the current player's inventory at `row`,
+    /// `col`. Their turn goes on, so they still drop a piece afterwards.
+    pub fn use_power_up(
+        &mut self,
+        glyph: char,
+        row: usize,
+        col: usize,
+    ) -> Result<Vec<Activation>, String> {
+        if self.targeting.is_some() {
+            return Err("Pick a target first.".to_string());
+        }
+        if row >= self.rows || col >= self.cols {
+            return Err("That cell is off the board.".to_string());
+        }
+        let inventory = &mut self.inventories[player_index(self.current_player)];
+        let Some(index) = inventory.iter().position(|&item| item == glyph) else {
+            return Err("You haven't collected that power-up.".to_string());
+        };
+        inventory.remove(index);
+
+        let mut power_ups = self.activate_power_up(row, col, glyph);
+        if let Some(last) = power_ups.last_mut() {
+            last.effects.extend(self.settle());
+        }
+        Ok(power_ups)
+    }
+
+    /// Waits for the current player to pick an opponent's piece within
+    /// `radius` cells of the power-up in the cell. Does nothing if there is
+    /// none, or if another power-up is already waiting. Returns whether it
+    /// is waiting.
+    pub fn start_targeting(&mut self, row: usize, col: usize, glyph: char, radius: usize) -> bool {
+        let targeting = Targeting {
+            row,
+            col,
+            glyph,
+            radius,
+            ends_turn: false,
+        };
+        if self.targeting.is_some() || self.targets_of(&targeting).is_empty() {
+            return false;
+        }
+        self.targeting = Some(targeting);
+        true
+    }
+
+    /// The power-up waiting for its target, if any.
+    pub fn targeting(&self) -> Option<&Targeting> {
+        self.targeting.as_ref()
+    }
+
+    /// Cells the waiting power-up can be aimed at.
+    pub fn targets(&self) -> Vec<(usize, usize)> {
+        self.targeting
+            .as_ref()
+            .map_or_else(Vec::new, |targeting| self.targets_of(targeting))
+    }
+
+    fn targets_of(&self, targeting: &Targeting) -> Vec<(usize, usize)> {
+        (0..self.rows)
+            .flat_map(|row| (0..self.cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.is_target(targeting, row, col))
+            .collect()
+    }
+
+    fn is_target(&self, targeting: &Targeting, row: usize, col: usize) -> bool {
+        let cell = self.board[row][col];
+        (cell == PLAYER_X || cell == PLAYER_O)
+            && cell != self.current_player
+            && row.abs_diff(targeting.row) <= targeting.radius
+            && col.abs_diff(targeting.col) <= targeting.radius
+    }
+
+    /// Destroys the opponent's piece in the cell for the waiting power-up,
+    /// and lets what was above it fall.
+    pub fn choose_target(&mut self, row: usize, col: usize) -> Result<Activation, String> {
+        let Some(targeting) = self.targeting else {
+            return Err("Nothing is waiting for a target.".to_string());
+        };
+        if row >= self.rows || col >= self.cols {
+            return Err("That cell is off the board.".to_string());
+        }
+        if !self.is_target(&targeting, row, col) {
+            return Err(format!(
+                "Pick one of your opponent's pieces within {} cells.",
+                targeting.radius
+            ));
+        }
 
-        Err("Column is full.".to_string())
+        self.targeting = None;
+        self.clear_cell(row, col);
+        let mut effects = vec![Effect::Cleared { row, col }];
+        effects.extend(self.settle());
+        Ok(Activation {
+            row,
+            col,
+            glyph: targeting.glyph,
+            effects,
+        })
+    }
+
+    /// Calls off the waiting power-up without destroying anything.
+    pub fn cancel_target(&mut self) {
+        self.targeting = None;
     }
 
     pub fn switch_player(&mut self) {
-        if self.skip_turn {
-            self.skip_turn = false;
+        if self.skip_turns > 0 {
+            self.skip_turns -= 1;
         } else {
             self.current_player = if self.current_player == PLAYER_X {
                 PLAYER_O
@@ -139,98 +667,201 @@ impl Game {
         }
     }
 
-    pub fn activate_power_up(&mut self, row: usize, col: usize) {
+    /// Sets off the power-up marked by `glyph`, if it is one, in the cell.
+    /// With chain reactions, power-ups whose cells its effects change go off
+    /// next, in board order, up to `MAX_CHAIN_DEPTH` steps from the first.
+    pub fn activate_power_up(&mut self, row: usize, col: usize, glyph: char) -> Vec<Activation> {
+        let mut activations = Vec::new();
         if !self.power_ups_enabled {
-            return;
+            return activations;
         }
-        match self.board[row][col] {
-            'B' => {
-                self.use_bomb(row, col);
-            }
-            'S' => {
-                self.skip_turn = true;
-            }
-            'H' => {
-                self.place_obstacles(row, col);
+
+        let mut queue = VecDeque::from([(row, col, glyph, 0)]);
+        // Cells that have gone off or are queued to; each goes off once.
+        let mut spent = vec![(row, col)];
+        while let Some((row, col, glyph, depth)) = queue.pop_front() {
+            let Some(power_up) = power_ups::registry().get(glyph) else {
+                continue;
+            };
+            let armed = self.power_up_cells();
+            let effects = power_up.on_trigger(self, row, col);
+
+            if self.settings.chain_reactions && depth < MAX_CHAIN_DEPTH {
+                for (hit_row, hit_col, hit_glyph) in armed {
+                    if !spent.contains(&(hit_row, hit_col))
+                        && self.board[hit_row][hit_col] != hit_glyph
+                    {
+                        spent.push((hit_row, hit_col));
+                        queue.push_back((hit_row, hit_col, hit_glyph, depth + 1));
+                    }
+                }
             }
-            _ => {}
+            activations.push(Activation {
+                row,
+                col,
+                glyph,
+                effects,
+            });
         }
+        activations
+    }
+
+    /// Every power-up still waiting on the board, bottom row first.
+    fn power_up_cells(&self) -> Vec<(usize, usize, char)> {
+        (0..self.rows)
+            .flat_map(|row| (0..self.cols).map(move |col| (row, col)))
+            .map(|(row, col)| (row, col, self.board[row][col]))
+            .filter(|&(_, _, cell)| power_ups::is_power_up(cell))
+            .collect()
     }
 
-    pub fn place_obstacles(&mut self, row: usize, col: usize) {
-        let deltas = [(0, -1), (0, 1)];
-        for (dr, dc) in deltas.iter() {
-            let nr = row as isize + dr;
-            let nc = col as isize + dc;
+    /// Empties a cell, whatever is in it.
+    pub fn clear_cell(&mut self, row: usize, col: usize) {
+        self.board[row][col] = EMPTY;
+    }
 
-            if nr >= 0 && nr < self.rows as isize && nc >= 0 && nc < self.cols as isize {
-                let mut target_row = nr as usize;
-                let target_col = nc as usize;
+    /// The cell something falling from `row` down `col` comes to rest in.
+    fn resting_row(&self, row: usize, col: usize) -> usize {
+        let mut target_row = row;
+        while target_row > 0 && self.is_open(target_row - 1, col) {
+            target_row -= 1;
+        }
+        target_row
+    }
+
+    /// Drops an obstacle down `col` from `row`, or from on top of whatever
+    /// fills that cell, and returns the row it comes to rest in. A power-up
+    /// it comes to rest on is buried. Returns `None` if the column is full.
+    pub fn drop_obstacle(&mut self, row: usize, col: usize) -> Option<usize> {
+        let start = (row..self.rows).find(|&row| self.is_open(row, col))?;
+        let target_row = self.resting_row(start, col);
+        self.board[target_row][col] = OBSTACLE;
+        Some(target_row)
+    }
 
-                while target_row > 0
-                    && (self.board[target_row - 1][target_col] == EMPTY
-                        || ['B', 'S', 'H'].contains(&self.board[target_row - 1][target_col]))
-                {
+    /// Lets pieces and obstacles left hanging over gaps fall, if the rules
+    /// have gravity, and reports what moved. Columns settle from the bottom
+    /// up, so a stack falls together. Power-ups stay put and hold up
+    /// whatever falls onto them.
+    pub fn settle(&mut self) -> Vec<Effect> {
+        let mut falls = Vec::new();
+        if !self.settings.gravity {
+            return falls;
+        }
+        for col in 0..self.cols {
+            for row in 1..self.rows {
+                let cell = self.board[row][col];
+                if ![PLAYER_X, PLAYER_O, OBSTACLE].contains(&cell) {
+                    continue;
+                }
+                let mut target_row = row;
+                while target_row > 0 && self.board[target_row - 1][col] == EMPTY {
                     target_row -= 1;
                 }
-
-                self.board[target_row][target_col] = OBSTACLE;
+                if target_row != row {
+                    self.board[target_row][col] = cell;
+                    self.board[row][col] = EMPTY;
+                    falls.push(Effect::Fell {
+                        col,
+                        from_row: row,
+                        to_row: target_row,
+                    });
+                }
             }
         }
+        falls
+    }
+
+    /// Puts `cell` in the cell, replacing whatever was there.
+    pub fn set_cell(&mut self, row: usize, col: usize, cell: char) {
+        self.board[row][col] = cell;
+    }
+
+    /// Ends the game in `winner`'s favour.
+    pub fn declare_winner(&mut self, winner: char) {
+        self.declared_winner = Some(winner);
     }
 
-    pub fn use_bomb(&mut self, row: usize, col: usize) {
-        if row > 0 {
-            self.board[row][col] = EMPTY;
-            self.board[row - 1][col] = EMPTY;
-        } else if row == 0 {
-            self.board[0][col] = EMPTY;
+    /// Keeps the turn with the current player `turns` more times.
+    pub fn skip_turns(&mut self, turns: u32) {
+        self.skip_turns += turns;
+    }
+
+    /// Hands the opponent's piece in the cell to the current player, and
+    /// returns its new owner.
+    pub fn convert_piece(&mut self, row: usize, col: usize) -> Option<char> {
+        let cell = self.board[row][col];
+        if cell != PLAYER_X && cell != PLAYER_O || cell == self.current_player {
+            return None;
         }
-        self.skip_turn = true;
+        self.board[row][col] = self.current_player;
+        Some(self.current_player)
+    }
+
+    pub fn win_length(&self) -> usize {
+        self.settings.win_length
     }
 
     pub fn check_winner(&self) -> Option<char> {
+        if self.declared_winner.is_some() {
+            return self.declared_winner;
+        }
+        let length = self.settings.win_length as isize;
+        let directions = [(0, 1), (1, 0), (1, 1), (-1, 1)];
+
         for row in 0..self.rows {
             for col in 0..self.cols {
-                if self.board[row][col] != EMPTY {
-                    let player = self.board[row][col];
-                    if col + 3 < self.cols
-                        && self.board[row][col + 1] == player
-                        && self.board[row][col + 2] == player
-                        && self.board[row][col + 3] == player
-                    {
-                        return Some(player);
-                    }
-
-                    if row + 3 < self.rows
-                        && self.board[row + 1][col] == player
-                        && self.board[row + 2][col] == player
-                        && self.board[row + 3][col] == player
-                    {
-                        return Some(player);
-                    }
-
-                    if row + 3 < self.rows
-                        && col + 3 < self.cols
-                        && self.board[row + 1][col + 1] == player
-                        && self.board[row + 2][col + 2] == player
-                        && self.board[row + 3][col + 3] == player
-                    {
-                        return Some(player);
-                    }
+                let player = self.board[row][col];
+                if player != PLAYER_X && player != PLAYER_O {
+                    continue;
+                }
 
-                    if row >= 3
-                        && col + 3 < self.cols
-                        && self.board[row - 1][col + 1] == player
-                        && self.board[row - 2][col + 2] == player
-                        && self.board[row - 3][col + 3] == player
-                    {
+                for (dr, dc) in directions {
+                    let connected = (1..length).all(|step| {
+                        let r = row as isize + dr * step;
+                        let c = col as isize + dc * step;
+                        r >= 0
+                            && c >= 0
+                            && (r as usize) < self.rows
+                            && (c as usize) < self.cols
+                            && self.board[r as usize][c as usize] == player
+                    });
+                    if connected {
                         return Some(player);
                     }
                 }
             }
         }
-        None
+        self.mod_winner()
+    }
+
+    /// The winner under the mods' rules, reusing the last verdict while the
+    /// board and the player to move are the same.
+    fn mod_winner(&self) -> Option<char> {
+        if !mods::has_rules() {
+            return None;
+        }
+        let lock = || {
+            self.mod_verdict
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+        if let Some(verdict) = lock()
+            .as_ref()
+            .filter(|verdict| verdict.board == self.board && verdict.player == self.current_player)
+        {
+            return verdict.winner;
+        }
+
+        // Not locked while the scripts run, in case they check the board too.
+        let winner = mods::check_winner(self);
+        *lock() = Some(Verdict {
+            board: self.board.clone(),
+            player: self.current_player,
+            winner,
+        });
+        winner
     }
 
     pub fn is_full(&self) -> bool {
@@ -240,19 +871,19 @@ impl Game {
     }
 
     pub fn expand_board(&mut self) {
-        self.rows = EXPANDED_ROWS;
-        self.cols = EXPANDED_COLS;
-        let mut new_board = vec![vec![EMPTY; EXPANDED_COLS]; EXPANDED_ROWS];
+        let (old_rows, old_cols) = (self.rows, self.cols);
+        let (new_rows, new_cols) = self.settings.expanded_size();
+        self.rows = new_rows;
+        self.cols = new_cols;
+        let mut new_board = vec![vec![EMPTY; new_cols]; new_rows];
 
-        for r in 0..ROWS {
-            for c in 0..COLS {
-                new_board[r][c] = self.board[r][c];
-            }
+        for (new_row, old_row) in new_board.iter_mut().zip(self.board.iter()).take(old_rows) {
+            new_row[..old_cols].copy_from_slice(&old_row[..old_cols]);
         }
 
         self.board = new_board;
         let num_new_power_ups = if self.power_ups_enabled {
-            (EXPANDED_ROWS * EXPANDED_COLS - ROWS * COLS) / 10
+            (new_rows * new_cols - old_rows * old_cols) / 10
         } else {
             0
         };
@@ -288,6 +919,30 @@ impl Game {
                 println!("{}", err);
                 continue;
             }
+            while self.targeting.is_some() {
+                self.print_board();
+                print!("Pick a piece to destroy (row column), or press Enter to cancel: ");
+                io::stdout().flush().unwrap();
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input).unwrap();
+                if input.trim().is_empty() {
+                    self.cancel_target();
+                    continue;
+                }
+                let cell: Vec<usize> = input
+                    .split_whitespace()
+                    .filter_map(|part| part.parse().ok())
+                    .collect();
+                match cell[..] {
+                    [row, col] => {
+                        if let Err(err) = self.choose_target(row, col) {
+                            println!("{}", err);
+                        }
+                    }
+                    _ => println!("Enter a row and a column, e.g. 0 3."),
+                }
+            }
 
             if let Some(winner) = self.check_winner() {
                 self.print_board();
@@ -296,7 +951,7 @@ impl Game {
             }
 
             if self.is_full() {
-                if !self.expanded {
+                if self.can_expand() {
                     self.expand_board();
                     self.expanded = true;
                     continue;
@@ -308,6 +963,9 @@ impl Game {
             }
 
             self.switch_player();
+            for (row, col) in self.respawn_power_ups() {
+                println!("A power-up appeared in row {}, column {}.", row, col);
+            }
         }
     }
 }
@@ -317,3 +975,441 @@ pub fn backend() {
     let mut game = Game::new(true);
     game.play();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placement;
+
+    /// A game on an empty board, with power-ups on but none placed.
+    fn empty_game(settings: GameSettings) -> Game {
+        let mut game = Game::with_settings(settings);
+        game.power_ups_enabled = true;
+        game.board = vec![vec![EMPTY; game.cols]; game.rows];
+        game
+    }
+
+    #[test]
+    fn settle_drops_hanging_stacks_together() {
+        let mut game = empty_game(GameSettings {
+            gravity: true,
+            ..GameSettings::default()
+        });
+        game.set_cell(2, 1, PLAYER_X);
+        game.set_cell(3, 1, OBSTACLE);
+        game.set_cell(4, 1, PLAYER_O);
+
+        let falls = game.settle();
+
+        assert_eq!(
+            falls,
+            vec![
+                Effect::Fell {
+                    col: 1,
+                    from_row: 2,
+                    to_row: 0
+                },
+                Effect::Fell {
+                    col: 1,
+                    from_row: 3,
+                    to_row: 1
+                },
+                Effect::Fell {
+                    col: 1,
+                    from_row: 4,
+                    to_row: 2
+                },
+            ]
+        );
+        let column: Vec<char> = (0..5).map(|row| game.board[row][1]).collect();
+        assert_eq!(column, [PLAYER_X, OBSTACLE, PLAYER_O, EMPTY, EMPTY]);
+    }
+
+    #[test]
+    fn settle_leaves_the_board_alone_without_gravity() {
+        let mut game = empty_game(GameSettings::default());
+        game.set_cell(3, 0, PLAYER_X);
+
+        assert!(game.settle().is_empty());
+        assert_eq!(game.board[3][0], PLAYER_X);
+    }
+
+    #[test]
+    fn settle_stops_on_power_ups() {
+        let mut game = empty_game(GameSettings {
+            gravity: true,
+            ..GameSettings::default()
+        });
+        game.set_cell(0, 2, 'B');
+        game.set_cell(3, 2, PLAYER_O);
+
+        let falls = game.settle();
+
+        assert_eq!(
+            falls,
+            vec![Effect::Fell {
+                col: 2,
+                from_row: 3,
+                to_row: 1
+            }]
+        );
+        assert_eq!(game.board[0][2], 'B');
+        assert_eq!(game.board[1][2], PLAYER_O);
+    }
+
+    #[test]
+    fn pieces_fall_into_the_gap_a_bomb_leaves() {
+        let mut game = empty_game(GameSettings {
+            gravity: true,
+            ..GameSettings::default()
+        });
+        for (row, cell) in [PLAYER_X, PLAYER_O, PLAYER_X, PLAYER_O]
+            .into_iter()
+            .enumerate()
+        {
+            game.set_cell(row, 3, cell);
+        }
+        game.inventories[0].push('B');
+
+        let activations = game.use_power_up('B', 2, 3).unwrap();
+
+        assert_eq!(
+            activations[0].effects.last(),
+            Some(&Effect::Fell {
+                col: 3,
+                from_row: 3,
+                to_row: 1
+            })
+        );
+        let column: Vec<char> = (0..4).map(|row| game.board[row][3]).collect();
+        assert_eq!(column, [PLAYER_X, PLAYER_O, EMPTY, EMPTY]);
+    }
+
+    #[test]
+    fn power_ups_hit_by_an_effect_go_off_in_turn() {
+        let mut game = empty_game(GameSettings {
+            chain_reactions: true,
+            ..GameSettings::default()
+        });
+        game.set_cell(0, 0, 'S');
+        game.set_cell(1, 0, 'B');
+        game.set_cell(1, 4, 'S');
+
+        let activations = game.activate_power_up(2, 0, 'B');
+
+        let cells: Vec<(usize, usize, char)> = activations
+            .iter()
+            .map(|activation| (activation.row, activation.col, activation.glyph))
+            .collect();
+        assert_eq!(cells, [(2, 0, 'B'), (1, 0, 'B'), (0, 0, 'S')]);
+        // The skip out of the bomb's reach is left waiting.
+        assert_eq!(game.board[1][4], 'S');
+    }
+
+    #[test]
+    fn power_ups_only_go_off_one_at_a_time_without_chain_reactions() {
+        let mut game = empty_game(GameSettings::default());
+        game.set_cell(1, 0, 'B');
+
+        let activations = game.activate_power_up(2, 0, 'B');
+
+        assert_eq!(activations.len(), 1);
+        assert_eq!(game.board[1][0], EMPTY);
+    }
+
+    #[test]
+    fn chain_reactions_stop_at_the_depth_limit() {
+        let mut game = empty_game(GameSettings {
+            rows: MAX_BOARD_SIZE,
+            chain_reactions: true,
+            ..GameSettings::default()
+        });
+        for row in 0..=10 {
+            game.set_cell(row, 0, 'B');
+        }
+
+        let activations = game.activate_power_up(11, 0, 'B');
+
+        assert_eq!(activations.len(), MAX_CHAIN_DEPTH + 1);
+        assert_eq!(activations.last().unwrap().row, 11 - MAX_CHAIN_DEPTH);
+    }
+
+    /// X drops onto a targeted bomb next to an O piece.
+    fn targeting_game() -> Game {
+        let mut game = empty_game(GameSettings::default());
+        game.set_cell(0, 0, 'T');
+        game.set_cell(0, 1, PLAYER_O);
+        game.drop_piece(0).unwrap();
+        game
+    }
+
+    #[test]
+    fn targeted_bomb_destroys_the_chosen_piece() {
+        let mut game = targeting_game();
+        assert_eq!(game.targets(), [(0, 1)]);
+        assert!(game.targeting().unwrap().ends_turn);
+        assert!(game.drop_piece(2).is_err());
+
+        let activation = game.choose_target(0, 1).unwrap();
+
+        assert_eq!(activation.glyph, 'T');
+        assert_eq!(activation.effects, [Effect::Cleared { row: 0, col: 1 }]);
+        assert_eq!(game.board[0][1], EMPTY);
+        assert!(game.targeting().is_none());
+    }
+
+    #[test]
+    fn targeted_bomb_rejects_cells_out_of_reach_or_not_the_opponents() {
+        let mut game = targeting_game();
+        game.set_cell(0, 3, PLAYER_O);
+
+        // Its own piece, an opponent's piece too far away, and an empty cell.
+        for (row, col) in [(0, 0), (0, 3), (1, 1)] {
+            assert!(game.choose_target(row, col).is_err());
+        }
+        assert!(game.targeting().is_some());
+        assert_eq!(game.board[0][3], PLAYER_O);
+    }
+
+    #[test]
+    fn cancelled_target_leaves_the_board_alone() {
+        let mut game = targeting_game();
+        let board = game.board.clone();
+
+        game.cancel_target();
+
+        assert!(game.targeting().is_none());
+        assert_eq!(game.board, board);
+        assert!(game.choose_target(0, 1).is_err());
+    }
+
+    #[test]
+    fn targeted_bomb_does_nothing_without_a_piece_in_reach() {
+        let mut game = empty_game(GameSettings::default());
+        game.set_cell(0, 0, 'T');
+        game.set_cell(0, 4, PLAYER_O);
+
+        let landing = game.drop_piece(0).unwrap();
+
+        assert!(game.targeting().is_none());
+        assert!(landing.power_ups[0].effects.is_empty());
+    }
+
+    #[test]
+    fn power_ups_respawn_every_few_turns_where_pieces_land() {
+        let mut game = empty_game(GameSettings {
+            respawn: Respawn::EveryTurns(2),
+            max_power_ups: 6,
+            ..GameSettings::default()
+        });
+        game.set_cell(0, 3, PLAYER_X);
+
+        assert!(game.respawn_power_ups().is_empty());
+        let spawned = game.respawn_power_ups();
+
+        assert_eq!(spawned.len(), 1);
+        let (row, col) = spawned[0];
+        assert_eq!(game.landing_row(col), Some(row));
+        assert!(power_ups::is_power_up(game.board[row][col]));
+        assert!(game.respawn_power_ups().is_empty());
+    }
+
+    #[test]
+    fn power_ups_stop_respawning_at_the_limit() {
+        let mut game = empty_game(GameSettings {
+            respawn: Respawn::EveryTurns(1),
+            max_power_ups: 2,
+            ..GameSettings::default()
+        });
+
+        assert_eq!(game.respawn_power_ups().len(), 1);
+        assert_eq!(game.respawn_power_ups().len(), 1);
+        assert!(game.respawn_power_ups().is_empty());
+        assert_eq!(game.power_up_cells().len(), 2);
+    }
+
+    #[test]
+    fn power_ups_never_respawn_when_off() {
+        let mut game = empty_game(GameSettings {
+            respawn: Respawn::Off,
+            max_power_ups: 6,
+            ..GameSettings::default()
+        });
+
+        for _ in 0..10 {
+            assert!(game.respawn_power_ups().is_empty());
+        }
+    }
+
+    #[test]
+    fn placed_power_ups_keep_their_distance_and_stay_within_reach() {
+        let settings = GameSettings {
+            power_ups: true,
+            power_up_count: 6,
+            placement: Placement {
+                min_distance: 2,
+                max_depth: 1,
+                ..Placement::default()
+            },
+            ..GameSettings::default()
+        };
+        for _ in 0..20 {
+            let game = Game::with_settings(settings.clone());
+            let report = placement::report(&game);
+
+            assert!(report.closest.is_some_and(|closest| closest >= 2));
+            assert!(game.power_up_cells().iter().all(|&(row, _, _)| row <= 1));
+        }
+    }
+
+    #[test]
+    fn mirrored_power_ups_come_in_pairs_within_the_count() {
+        // An odd count fills the middle column, or falls one short without one.
+        for (cols, expected) in [(7, 5), (8, 4)] {
+            let settings = GameSettings {
+                cols,
+                power_ups: true,
+                power_up_count: 5,
+                placement: Placement {
+                    mirrored: true,
+                    ..Placement::default()
+                },
+                ..GameSettings::default()
+            };
+            for _ in 0..20 {
+                let game = Game::with_settings(settings.clone());
+                let report = placement::report(&game);
+
+                assert_eq!(report.power_ups, expected);
+                assert_eq!(report.symmetry, 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn balanced_power_ups_split_evenly_between_the_players_rows() {
+        let settings = GameSettings {
+            power_ups: true,
+            power_up_count: 6,
+            placement: Placement {
+                parity_balanced: true,
+                ..Placement::default()
+            },
+            ..GameSettings::default()
+        };
+        for _ in 0..20 {
+            let game = Game::with_settings(settings.clone());
+            let report = placement::report(&game);
+
+            assert_eq!(report.power_ups, 6);
+            assert_eq!(report.first_player_rows, report.second_player_rows);
+        }
+    }
+
+    #[test]
+    fn mirrored_power_ups_respawn_in_pairs_up_to_the_limit() {
+        let mut game = empty_game(GameSettings {
+            respawn: Respawn::EveryTurns(1),
+            max_power_ups: 3,
+            power_ups: true,
+            power_up_count: 0,
+            placement: Placement {
+                mirrored: true,
+                ..Placement::default()
+            },
+            ..GameSettings::default()
+        });
+
+        assert!(!game.respawn_power_ups().is_empty());
+        for _ in 0..5 {
+            game.respawn_power_ups();
+            let report = placement::report(&game);
+            assert!(report.power_ups <= 3);
+            assert_eq!(report.symmetry, 1.0);
+        }
+    }
+
+    #[test]
+    fn default_settings_are_valid() {
+        assert_eq!(GameSettings::default().validate(), Ok(()));
+        let with_power_ups = GameSettings {
+            power_ups: true,
+            ..GameSettings::default()
+        };
+        assert_eq!(with_power_ups.validate(), Ok(()));
+    }
+
+    #[test]
+    fn board_size_must_be_within_bounds() {
+        for (rows, cols) in [(MIN_BOARD_SIZE - 1, COLS), (ROWS, MAX_BOARD_SIZE + 1)] {
+            let settings = GameSettings {
+                rows,
+                cols,
+                ..GameSettings::default()
+            };
+            assert!(settings.validate().is_err(), "{}x{}", rows, cols);
+        }
+    }
+
+    #[test]
+    fn win_length_must_fit_the_board() {
+        for win_length in [MIN_WIN_LENGTH - 1, COLS + 1] {
+            let settings = GameSettings {
+                win_length,
+                ..GameSettings::default()
+            };
+            assert_eq!(
+                settings.validate(),
+                Err(format!(
+                    "Win length must be between {} and {} on a {}x{} board.",
+                    MIN_WIN_LENGTH, COLS, ROWS, COLS
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn first_player_must_be_a_player() {
+        let settings = GameSettings {
+            first_player: OBSTACLE,
+            ..GameSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            Err("The first player must be X or O.".to_string())
+        );
+    }
+
+    #[test]
+    fn power_ups_must_be_known_and_fit_on_the_board() {
+        let power_ups = GameSettings {
+            power_ups: true,
+            ..GameSettings::default()
+        };
+        for (settings, err) in [
+            (
+                GameSettings {
+                    enabled_power_ups: Vec::new(),
+                    ..power_ups.clone()
+                },
+                "Enable at least one power-up type.".to_string(),
+            ),
+            (
+                GameSettings {
+                    enabled_power_ups: vec!['Z'],
+                    ..power_ups.clone()
+                },
+                "Unknown power-up type 'Z'.".to_string(),
+            ),
+            (
+                GameSettings {
+                    power_up_count: ROWS * COLS,
+                    ..power_ups.clone()
+                },
+                format!("{} power-ups do not fit on a 6x7 board.", ROWS * COLS),
+            ),
+        ] {
+            assert_eq!(settings.validate(), Err(err));
+        }
+    }
+}
@@ -1,6 +1,12 @@
 // main.rs
+mod ai;
+// Bevy systems routinely take many parameters and nested query types.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 mod frontend;
 mod game; // Import the backend game logic // Import the frontend logic
+mod mods;
+mod placement;
+mod power_ups;
 
 fn main() {
     frontend::run(); // Call the frontend main function
@@ -0,0 +1,436 @@
+// mods.rs
+// Rhai scripts that add power-ups and winning rules. Scripts only see a copy
+// of the board through a small API, and run under limits so a broken one
+// can't hang the game.
+use crate::game::{Game, EMPTY, OBSTACLE, PLAYER_O, PLAYER_X};
+use crate::power_ups::{self, Definition, Effect, Registry};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, FuncArgs, Scope, AST};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Steps a script may take per call before it is stopped.
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+/// How deeply expressions may nest, at the top level and inside functions.
+const MAX_EXPR_DEPTHS: (usize, usize) = (64, 64);
+const MAX_COLLECTION_SIZE: usize = 1_000;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The game as a script sees it. Scripts change this copy, and the changes
+/// are applied to the real game once the script has finished.
+#[derive(Debug, Clone)]
+struct ScriptGame {
+    board: Vec<Vec<char>>,
+    player: char,
+    skip_turns: u32,
+    winner: Option<char>,
+}
+
+impl ScriptGame {
+    fn new(game: &Game) -> Self {
+        Self {
+            board: game.get_board().clone(),
+            player: game.get_current_player(),
+            skip_turns: 0,
+            winner: None,
+        }
+    }
+
+    fn rows(&mut self) -> i64 {
+        self.board.len() as i64
+    }
+
+    fn cols(&mut self) -> i64 {
+        self.board[0].len() as i64
+    }
+
+    fn player(&mut self) -> char {
+        self.player
+    }
+
+    fn opponent(&mut self) -> char {
+        if self.player == PLAYER_X {
+            PLAYER_O
+        } else {
+            PLAYER_X
+        }
+    }
+
+    fn on_board(&mut self, row: i64, col: i64) -> bool {
+        (0..self.rows()).contains(&row) && (0..self.cols()).contains(&col)
+    }
+
+    fn index(&mut self, row: i64, col: i64) -> ScriptResult<(usize, usize)> {
+        if !self.on_board(row, col) {
+            return Err(format!("cell ({}, {}) is off the board", row, col).into());
+        }
+        Ok((row as usize, col as usize))
+    }
+
+    fn cell(&mut self, row: i64, col: i64) -> ScriptResult<char> {
+        let (row, col) = self.index(row, col)?;
+        Ok(self.board[row][col])
+    }
+
+    fn set_cell(&mut self, row: i64, col: i64, cell: char) -> ScriptResult<()> {
+        let (row, col) = self.index(row, col)?;
+        if ![EMPTY, PLAYER_X, PLAYER_O, OBSTACLE].contains(&cell) && !power_ups::is_power_up(cell) {
+            return Err(format!("'{}' is not a piece, obstacle or power-up", cell).into());
+        }
+        self.board[row][col] = cell;
+        Ok(())
+    }
+
+    fn skip_turns(&mut self, turns: i64) -> ScriptResult<()> {
+        if turns < 0 {
+            return Err("can't skip a negative number of turns".into());
+        }
+        self.skip_turns = self
+            .skip_turns
+            .saturating_add(turns.min(u32::MAX as i64) as u32);
+        Ok(())
+    }
+
+    fn end_game(&mut self, winner: char) -> ScriptResult<()> {
+        if winner != PLAYER_X && winner != PLAYER_O {
+            return Err(format!("'{}' is not a player", winner).into());
+        }
+        self.winner = Some(winner);
+        Ok(())
+    }
+
+    /// Applies the script's changes to `game` and describes them.
+    fn apply(self, game: &mut Game) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        for (row, cells) in self.board.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                if game.get_board()[row][col] == cell {
+                    continue;
+                }
+                game.set_cell(row, col, cell);
+                match cell {
+                    EMPTY => effects.push(Effect::Cleared { row, col }),
+                    OBSTACLE => effects.push(Effect::ObstaclePlaced { row, col }),
+                    PLAYER_X | PLAYER_O => effects.push(Effect::Converted {
+                        row,
+                        col,
+                        player: cell,
+                    }),
+                    // New power-ups are picked up by the frontend on its own.
+                    _ => {}
+                }
+            }
+        }
+        if self.skip_turns > 0 {
+            game.skip_turns(self.skip_turns);
+            effects.push(Effect::SkipTurns(self.skip_turns));
+        }
+        if let Some(winner) = self.winner {
+            game.declare_winner(winner);
+        }
+        effects
+    }
+}
+
+/// An engine that can't reach outside the game and stops runaway scripts.
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTHS.0, MAX_EXPR_DEPTHS.1)
+        .set_max_string_size(MAX_COLLECTION_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .disable_symbol("eval");
+
+    engine
+        .register_type_with_name::<ScriptGame>("Game")
+        .register_get("rows", ScriptGame::rows)
+        .register_get("cols", ScriptGame::cols)
+        .register_get("player", ScriptGame::player)
+        .register_get("opponent", ScriptGame::opponent)
+        .register_fn("on_board", ScriptGame::on_board)
+        .register_fn("cell", ScriptGame::cell)
+        .register_fn("set_cell", ScriptGame::set_cell)
+        .register_fn("skip_turns", ScriptGame::skip_turns)
+        .register_fn("end_game", ScriptGame::end_game);
+    engine
+}
+
+/// A loaded mod script.
+struct Script {
+    /// File it came from, for error messages.
+    source: String,
+    engine: Arc<Engine>,
+    ast: AST,
+    /// Set once an error has been reported, so a rule that fails on every
+    /// move doesn't flood the log.
+    failed: AtomicBool,
+}
+
+impl Script {
+    fn has_fn(&self, name: &str, params: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == params)
+    }
+
+    /// Calls `name` with `game` as `this`. Returns `None` if the script
+    /// failed, leaving `game` as it was.
+    fn call(&self, name: &str, game: &mut ScriptGame, args: impl FuncArgs) -> Option<Dynamic> {
+        let mut this = Dynamic::from(game.clone());
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut this);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &self.ast,
+            name,
+            args,
+        );
+
+        match (result, this.try_cast::<ScriptGame>()) {
+            (Ok(value), Some(changed)) => {
+                *game = changed;
+                Some(value)
+            }
+            (Ok(_), None) => {
+                self.report(name, "`this` was replaced");
+                None
+            }
+            (Err(err), _) => {
+                self.report(name, &err.to_string());
+                None
+            }
+        }
+    }
+
+    fn report(&self, name: &str, err: &str) {
+        if !self.failed.swap(true, Ordering::Relaxed) {
+            eprintln!("Mod {} failed in {}(): {}", self.source, name, err);
+        }
+    }
+
+    fn on_trigger(&self, game: &mut Game, row: usize, col: usize) -> Vec<Effect> {
+        let mut script_game = ScriptGame::new(game);
+        match self.call("on_trigger", &mut script_game, (row as i64, col as i64)) {
+            Some(_) => script_game.apply(game),
+            None => Vec::new(),
+        }
+    }
+
+    fn check_winner(&self, game: &Game) -> Option<char> {
+        let winner = self.call("check_winner", &mut ScriptGame::new(game), ())?;
+        if winner.is_unit() {
+            return None;
+        }
+        match winner.as_char() {
+            Ok(player) if player == PLAYER_X || player == PLAYER_O => Some(player),
+            _ => {
+                self.report("check_winner", "expected 'X', 'O' or ()");
+                None
+            }
+        }
+    }
+}
+
+/// Winning rules added by mods.
+static RULES: OnceLock<Vec<Arc<Script>>> = OnceLock::new();
+
+/// Loads the `.rhai` scripts in `dir`, in file name order, adding their
+/// power-ups to `registry` and putting their winning rules into play.
+/// Scripts that can't be used are skipped and described in the returned
+/// problems.
+pub fn load(dir: &Path, registry: &mut Registry) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut rules = Vec::new();
+
+    let paths = match fs::read_dir(dir) {
+        Ok(entries) => {
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+                .collect();
+            paths.sort();
+            paths
+        }
+        Err(_) => Vec::new(),
+    };
+
+    let engine = Arc::new(sandboxed_engine());
+    for path in paths {
+        let source = path.display().to_string();
+        match load_script(&engine, &path, registry) {
+            Ok(Some(rule)) => rules.push(rule),
+            Ok(None) => {}
+            Err(err) => problems.push(format!("{}: {}", source, err)),
+        }
+    }
+
+    RULES.set(rules).ok();
+    problems
+}
+
+/// Compiles one script and registers what it defines. Returns the script if
+/// it adds a winning rule.
+fn load_script(
+    engine: &Arc<Engine>,
+    path: &Path,
+    registry: &mut Registry,
+) -> Result<Option<Arc<Script>>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let ast = engine.compile(contents).map_err(|err| err.to_string())?;
+    let script = Arc::new(Script {
+        source: path.display().to_string(),
+        engine: engine.clone(),
+        ast,
+        failed: AtomicBool::new(false),
+    });
+
+    let defines_power_up = script.has_fn("power_up", 0);
+    let defines_rule = script.has_fn("check_winner", 0);
+    if !defines_power_up && !defines_rule {
+        return Err("defines neither power_up() nor check_winner()".to_string());
+    }
+
+    if defines_power_up {
+        if !script.has_fn("on_trigger", 2) {
+            return Err("defines power_up() but not on_trigger(row, col)".to_string());
+        }
+        let description = script
+            .engine
+            .call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut Scope::new(),
+                &script.ast,
+                "power_up",
+                (),
+            )
+            .map_err(|err| format!("power_up(): {}", err))?;
+        let definition: Definition = rhai::serde::from_dynamic(&description)
+            .map_err(|err| format!("power_up(): {}", err))?;
+
+        let trigger = script.clone();
+        registry.define_scripted(
+            definition,
+            Box::new(move |game, row, col| trigger.on_trigger(game, row, col)),
+        )?;
+    }
+
+    Ok(defines_rule.then_some(script))
+}
+
+/// Whether any mod adds a winning rule.
+pub fn has_rules() -> bool {
+    RULES.get().is_some_and(|rules| !rules.is_empty())
+}
+
+/// The winner under the mods' rules, if any.
+pub fn check_winner(game: &Game) -> Option<char> {
+    RULES.get()?.iter().find_map(|rule| rule.check_winner(game))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameSettings;
+
+    fn script(source: &str) -> Script {
+        let engine = Arc::new(sandboxed_engine());
+        let ast = engine.compile(source).unwrap();
+        Script {
+            source: "test.rhai".to_string(),
+            engine,
+            ast,
+            failed: AtomicBool::new(false),
+        }
+    }
+
+    /// Loads `source` as a script file, as `load` does.
+    fn load_source(name: &str, source: &str) -> Result<Option<Arc<Script>>, String> {
+        let path =
+            std::env::temp_dir().join(format!("connect4-{}-{}.rhai", name, std::process::id()));
+        fs::write(&path, source).unwrap();
+        let result = load_script(
+            &Arc::new(sandboxed_engine()),
+            &path,
+            &mut Registry::default(),
+        );
+        fs::remove_file(&path).ok();
+        result
+    }
+
+    fn empty_game() -> Game {
+        Game::with_settings(GameSettings::default())
+    }
+
+    #[test]
+    fn runaway_script_is_stopped_and_its_changes_dropped() {
+        let script = script("fn on_trigger(row, col) { this.set_cell(row, col, 'X'); loop {} }");
+        let mut game = empty_game();
+        let board = game.get_board().clone();
+
+        let effects = script.on_trigger(&mut game, 0, 0);
+
+        assert!(effects.is_empty());
+        assert_eq!(*game.get_board(), board);
+        assert!(script.failed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn set_cell_rejects_anything_but_pieces_obstacles_and_power_ups() {
+        let mut script_game = ScriptGame::new(&empty_game());
+
+        assert!(script_game.set_cell(0, 0, 'Z').is_err());
+        assert!(script_game.set_cell(0, 0, OBSTACLE).is_ok());
+        assert!(script_game.set_cell(0, 1, 'B').is_ok());
+        assert!(script_game.set_cell(-1, 0, PLAYER_X).is_err());
+    }
+
+    #[test]
+    fn apply_reports_what_the_script_changed() {
+        let mut game = empty_game();
+        game.set_cell(0, 0, PLAYER_X);
+        game.set_cell(0, 1, PLAYER_O);
+        let mut script_game = ScriptGame::new(&game);
+        script_game.set_cell(0, 0, EMPTY).unwrap();
+        script_game.set_cell(0, 1, PLAYER_X).unwrap();
+        script_game.skip_turns(2).unwrap();
+
+        let effects = script_game.apply(&mut game);
+
+        assert_eq!(
+            effects,
+            [
+                Effect::Cleared { row: 0, col: 0 },
+                Effect::Converted {
+                    row: 0,
+                    col: 1,
+                    player: PLAYER_X
+                },
+                Effect::SkipTurns(2),
+            ]
+        );
+        assert_eq!(game.get_board()[0][..2], [EMPTY, PLAYER_X]);
+    }
+
+    #[test]
+    fn power_up_without_on_trigger_is_rejected() {
+        let result = load_source(
+            "no-trigger",
+            r#"fn power_up() { #{ id: "test", name: "Test", glyph: 'Q' } }"#,
+        );
+
+        assert_eq!(
+            result.err().as_deref(),
+            Some("defines power_up() but not on_trigger(row, col)")
+        );
+    }
+}
@@ -0,0 +1,295 @@
+// placement.rs
+// Rules for where power-ups are placed, so neither player starts closer to
+// them, and a report on how fair a layout is.
+use crate::game::{Game, MAX_BOARD_SIZE};
+use crate::power_ups::{self, PowerUp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// How power-ups are spread over the board.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Placement {
+    /// Each power-up off the middle column has a twin of the same kind
+    /// mirrored across it.
+    pub mirrored: bool,
+    /// Keeps as many power-ups on even rows as on odd ones. The first
+    /// player tends to claim cells in the bottom row and every second row
+    /// above it, the second player the rows between.
+    pub parity_balanced: bool,
+    /// Fewest cells between two power-ups, in any direction; 1 lets them
+    /// touch.
+    pub min_distance: usize,
+    /// Most cells above where the next piece down its column would land a
+    /// power-up may start, so none is out of reach for long.
+    pub max_depth: usize,
+    /// Spawn weights by glyph, in place of the ones the power-ups define.
+    pub weights: HashMap<char, u32>,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            mirrored: false,
+            parity_balanced: false,
+            min_distance: 1,
+            max_depth: 2,
+            weights: HashMap::new(),
+        }
+    }
+}
+
+impl Placement {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..MAX_BOARD_SIZE).contains(&self.min_distance) {
+            return Err(format!(
+                "Power-up spacing must be between 1 and {}.",
+                MAX_BOARD_SIZE - 1
+            ));
+        }
+        if self.max_depth >= MAX_BOARD_SIZE {
+            return Err(format!(
+                "Power-up depth must be at most {}.",
+                MAX_BOARD_SIZE - 1
+            ));
+        }
+        if let Some(unknown) = self
+            .weights
+            .keys()
+            .find(|&&glyph| !power_ups::is_power_up(glyph))
+        {
+            return Err(format!("Unknown power-up type '{}' in weights.", unknown));
+        }
+        Ok(())
+    }
+
+    /// How often `power_up` is placed, relative to the others.
+    pub fn weight(&self, power_up: &dyn PowerUp) -> u32 {
+        self.weights
+            .get(&power_up.glyph())
+            .copied()
+            .unwrap_or_else(|| power_up.spawn_weight())
+    }
+
+    /// Whether a power-up in `cell` would be far enough from `others`.
+    pub fn spaced(&self, cell: (usize, usize), others: &[(usize, usize)]) -> bool {
+        others
+            .iter()
+            .all(|&other| distance(cell, other) >= self.min_distance)
+    }
+}
+
+/// Cells between two cells, counting diagonal steps as one.
+fn distance(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
+}
+
+/// How evenly the power-ups on a board are spread between the players.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FairnessReport {
+    pub power_ups: usize,
+    /// Power-ups left and right of the middle; the middle column counts for
+    /// neither side.
+    pub left: usize,
+    pub right: usize,
+    /// Power-ups on rows the first player tends to claim, and on the others.
+    pub first_player_rows: usize,
+    pub second_player_rows: usize,
+    /// Share of power-ups with a twin of the same kind mirrored across the
+    /// middle, from 0.0 to 1.0.
+    pub symmetry: f32,
+    /// Distance between the two closest power-ups, if there are two.
+    pub closest: Option<usize>,
+    /// Pieces that have to go into a power-up's column before one lands on
+    /// it, on average.
+    pub average_depth: f32,
+}
+
+impl FairnessReport {
+    /// Whether both sides and both players' rows hold as many power-ups.
+    pub fn is_balanced(&self) -> bool {
+        self.left == self.right && self.first_player_rows == self.second_player_rows
+    }
+}
+
+impl fmt::Display for FairnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} power-ups, {} left / {} right, {} on the first player's rows / {} on the second's, {:.0}% mirrored, ",
+            self.power_ups,
+            self.left,
+            self.right,
+            self.first_player_rows,
+            self.second_player_rows,
+            self.symmetry * 100.0,
+        )?;
+        match self.closest {
+            Some(closest) => write!(f, "closest {} apart, ", closest)?,
+            None => write!(f, "none close, ")?,
+        }
+        write!(
+            f,
+            "{:.1} pieces deep on average ({})",
+            self.average_depth,
+            if self.is_balanced() {
+                "balanced"
+            } else {
+                "unbalanced"
+            }
+        )
+    }
+}
+
+/// Describes how fairly the power-ups on `game`'s board are placed.
+pub fn report(game: &Game) -> FairnessReport {
+    let board = game.get_board();
+    let cols = board[0].len();
+    let cells: Vec<(usize, usize, char)> = board
+        .iter()
+        .enumerate()
+        .flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .map(move |(col, &cell)| (row, col, cell))
+        })
+        .filter(|&(_, _, cell)| power_ups::is_power_up(cell))
+        .collect();
+
+    let count = |keep: &dyn Fn(usize, usize) -> bool| {
+        cells
+            .iter()
+            .filter(|&&(row, col, _)| keep(row, col))
+            .count()
+    };
+    let mirrored = cells
+        .iter()
+        .filter(|&&(row, col, glyph)| board[row][cols - 1 - col] == glyph)
+        .count();
+    let closest = cells
+        .iter()
+        .enumerate()
+        .flat_map(|(index, &(row, col, _))| {
+            cells[index + 1..]
+                .iter()
+                .map(move |&(other_row, other_col, _)| distance((row, col), (other_row, other_col)))
+        })
+        .min();
+    let depth: usize = cells
+        .iter()
+        .map(|&(row, col, _)| row - game.landing_row(col).unwrap_or(row))
+        .sum();
+    let share = |part: usize| {
+        if cells.is_empty() {
+            0.0
+        } else {
+            part as f32 / cells.len() as f32
+        }
+    };
+
+    FairnessReport {
+        power_ups: cells.len(),
+        left: count(&|_, col| 2 * col + 1 < cols),
+        right: count(&|_, col| 2 * col + 1 > cols),
+        first_player_rows: count(&|row, _| row % 2 == 0),
+        second_player_rows: count(&|row, _| row % 2 == 1),
+        symmetry: share(mirrored),
+        closest,
+        average_depth: share(depth),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameSettings, PLAYER_X};
+
+    #[test]
+    fn validate_rejects_spacing_depth_and_weights_out_of_range() {
+        assert!(Placement::default().validate().is_ok());
+        for placement in [
+            Placement {
+                min_distance: 0,
+                ..Placement::default()
+            },
+            Placement {
+                max_depth: MAX_BOARD_SIZE,
+                ..Placement::default()
+            },
+            Placement {
+                weights: HashMap::from([('X', 1)]),
+                ..Placement::default()
+            },
+        ] {
+            assert!(placement.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn spaced_counts_diagonal_steps_as_one() {
+        let placement = Placement {
+            min_distance: 2,
+            ..Placement::default()
+        };
+
+        assert!(!placement.spaced((1, 1), &[(2, 2)]));
+        assert!(placement.spaced((1, 1), &[(3, 3), (1, 3)]));
+        assert!(placement.spaced((1, 1), &[]));
+    }
+
+    #[test]
+    fn weights_override_the_power_ups_own() {
+        let placement = Placement {
+            weights: HashMap::from([('B', 5)]),
+            ..Placement::default()
+        };
+        let registry = power_ups::registry();
+
+        assert_eq!(placement.weight(registry.get('B').unwrap()), 5);
+        let skip = registry.get('S').unwrap();
+        assert_eq!(placement.weight(skip), skip.spawn_weight());
+    }
+
+    #[test]
+    fn report_describes_the_layout() {
+        let mut game = Game::with_settings(GameSettings::default());
+        game.set_cell(0, 0, 'B');
+        game.set_cell(0, 6, 'B');
+        game.set_cell(1, 3, 'S');
+        game.set_cell(0, 1, PLAYER_X);
+        game.set_cell(2, 1, 'T');
+
+        let report = report(&game);
+
+        assert_eq!(
+            report,
+            FairnessReport {
+                power_ups: 4,
+                left: 2,
+                right: 1,
+                first_player_rows: 3,
+                second_player_rows: 1,
+                symmetry: 0.75,
+                closest: Some(2),
+                average_depth: 0.5,
+            }
+        );
+        assert!(!report.is_balanced());
+        assert_eq!(
+            report.to_string(),
+            "4 power-ups, 2 left / 1 right, 3 on the first player's rows / 1 on the second's, \
+             75% mirrored, closest 2 apart, 0.5 pieces deep on average (unbalanced)"
+        );
+    }
+
+    #[test]
+    fn report_on_an_empty_board_is_balanced() {
+        let report = report(&Game::with_settings(GameSettings::default()));
+
+        assert_eq!(report.power_ups, 0);
+        assert_eq!(report.closest, None);
+        assert!(report.is_balanced());
+    }
+}
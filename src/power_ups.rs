@@ -0,0 +1,347 @@
+// power_ups.rs
+// Power-ups hidden in the board, and the registry the game and the frontend
+// look them up in. The built-in ones are defined in `assets/power_ups/`.
+mod definition;
+
+pub use definition::Definition;
+
+use crate::game::{Game, EMPTY, OBSTACLE, PLAYER_O, PLAYER_X};
+use definition::DefinedPowerUp;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Power-ups shipped with the game, by file name. Definition files with the
+/// same id replace them.
+const BUILT_IN_POWER_UPS: [(&str, &str); 4] = [
+    ("bomb.ron", include_str!("../assets/power_ups/bomb.ron")),
+    ("skip.ron", include_str!("../assets/power_ups/skip.ron")),
+    (
+        "obstacle.ron",
+        include_str!("../assets/power_ups/obstacle.ron"),
+    ),
+    (
+        "targeted_bomb.ron",
+        include_str!("../assets/power_ups/targeted_bomb.ron"),
+    ),
+];
+
+/// Something a power-up did to the board, for the frontend to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Whatever was in the cell was removed.
+    Cleared { row: usize, col: usize },
+    /// An obstacle came to rest in the cell.
+    ObstaclePlaced { row: usize, col: usize },
+    /// The player who set it off moves again this many times.
+    SkipTurns(u32),
+    /// The piece in the cell now belongs to `player`.
+    Converted {
+        row: usize,
+        col: usize,
+        player: char,
+    },
+    /// The piece or obstacle in `from_row` fell into a gap down to `to_row`.
+    Fell {
+        col: usize,
+        from_row: usize,
+        to_row: usize,
+    },
+}
+
+/// How the frontend plays a power-up going off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Cue {
+    /// A blast of sparks and debris.
+    Explosion,
+    /// A ring spreading out from the cell, with the power-up's icon.
+    Shockwave,
+    /// A low rumble; anything it drops raises dust on landing.
+    Rumble,
+}
+
+/// Where on the board a power-up may be placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum SpawnRegion {
+    #[default]
+    Anywhere,
+    /// Rows counted from the bottom, both ends included.
+    Rows(usize, usize),
+    /// Columns counted from the left, both ends included.
+    Columns(usize, usize),
+}
+
+impl SpawnRegion {
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        match *self {
+            SpawnRegion::Anywhere => true,
+            SpawnRegion::Rows(from, to) => (from..=to).contains(&row),
+            SpawnRegion::Columns(from, to) => (from..=to).contains(&col),
+        }
+    }
+}
+
+/// How a power-up looks on the board.
+#[derive(Debug, Clone)]
+pub struct Visual {
+    /// Letter drawn on flat cells.
+    pub label: String,
+    /// sRGB color used when the theme doesn't pick one.
+    pub color: [f32; 3],
+    /// Image in the assets folder, drawn by skins that use icons.
+    pub icon: Option<String>,
+    pub cue: Cue,
+}
+
+/// A kind of power-up.
+pub trait PowerUp: Send + Sync {
+    /// Name themes and skins refer to it by.
+    fn id(&self) -> &str;
+
+    /// Name shown to players.
+    fn name(&self) -> &str;
+
+    /// Character marking it on the board.
+    fn glyph(&self) -> char;
+
+    /// How often it is placed, relative to the other enabled power-ups.
+    fn spawn_weight(&self) -> u32 {
+        1
+    }
+
+    fn spawn_region(&self) -> &SpawnRegion {
+        &SpawnRegion::Anywhere
+    }
+
+    /// Sets the power-up off when a piece lands on it at `row`, `col`. The
+    /// piece takes the cell afterwards unless this changed what is in it.
+    fn on_trigger(&self, game: &mut Game, row: usize, col: usize) -> Vec<Effect>;
+
+    fn visual(&self) -> &Visual;
+}
+
+/// Code run when a power-up is set off, e.g. from a script.
+pub type Trigger = Box<dyn Fn(&mut Game, usize, usize) -> Vec<Effect> + Send + Sync>;
+
+/// A power-up that went off, where, and what it did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activation {
+    pub row: usize,
+    pub col: usize,
+    pub glyph: char,
+    pub effects: Vec<Effect>,
+}
+
+/// Every kind of power-up the game knows.
+#[derive(Default)]
+pub struct Registry {
+    power_ups: Vec<Box<dyn PowerUp>>,
+}
+
+/// Reads a definition, reporting problems against `source`.
+fn parse_definition(source: &str, contents: &str) -> Result<Definition, String> {
+    ron::from_str(contents).map_err(|err| format!("{}: {}", source, err))
+}
+
+impl Registry {
+    pub fn built_in() -> Self {
+        let mut registry = Registry::default();
+        for (file_name, contents) in BUILT_IN_POWER_UPS {
+            let definition =
+                parse_definition(file_name, contents).expect("built-in power-up should parse");
+            registry
+                .define(definition, None)
+                .expect("built-in power-up should be valid");
+        }
+        registry
+    }
+
+    /// The built-in power-ups plus the `.ron` definitions in `dir`, in file
+    /// name order. Definitions that can't be used are skipped and described
+    /// in the returned problems.
+    pub fn load(dir: &Path) -> (Self, Vec<String>) {
+        let mut registry = Self::built_in();
+        let mut problems = Vec::new();
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return (registry, problems);
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let source = path.display().to_string();
+            let result = fs::read_to_string(&path)
+                .map_err(|err| format!("{}: {}", source, err))
+                .and_then(|contents| parse_definition(&source, &contents))
+                .and_then(|definition| {
+                    registry
+                        .define(definition, None)
+                        .map_err(|err| format!("{}: {}", source, err))
+                });
+            if let Err(problem) = result {
+                problems.push(problem);
+            }
+        }
+        (registry, problems)
+    }
+
+    /// Adds a power-up defined by a script, replacing any with the same id.
+    pub fn define_scripted(
+        &mut self,
+        definition: Definition,
+        script: Trigger,
+    ) -> Result<(), String> {
+        self.define(definition, Some(script))
+    }
+
+    /// Adds a defined power-up, replacing any with the same id in place.
+    fn define(&mut self, definition: Definition, script: Option<Trigger>) -> Result<(), String> {
+        let power_up = DefinedPowerUp::new(definition, script)?;
+        let replaced = self
+            .power_ups
+            .iter()
+            .position(|existing| existing.id() == power_up.id());
+        let old = replaced.map(|index| self.power_ups.remove(index));
+
+        if let Err(err) = self.register(Box::new(power_up)) {
+            if let (Some(index), Some(old)) = (replaced, old) {
+                self.power_ups.insert(index, old);
+            }
+            return Err(err);
+        }
+        if let Some(index) = replaced {
+            let new = self.power_ups.pop().expect("just registered");
+            self.power_ups.insert(index, new);
+        }
+        Ok(())
+    }
+
+    /// Adds a kind of power-up. Its glyph and id must not be taken.
+    pub fn register(&mut self, power_up: Box<dyn PowerUp>) -> Result<(), String> {
+        let glyph = power_up.glyph();
+        if [EMPTY, PLAYER_X, PLAYER_O, OBSTACLE].contains(&glyph) {
+            return Err(format!(
+                "glyph '{}' of '{}' is already used by the board.",
+                glyph,
+                power_up.id()
+            ));
+        }
+        if let Some(existing) = self
+            .power_ups
+            .iter()
+            .find(|p| p.glyph() == glyph || p.id() == power_up.id())
+        {
+            return Err(format!(
+                "'{}' uses the same id or glyph as '{}'.",
+                power_up.id(),
+                existing.id()
+            ));
+        }
+        self.power_ups.push(power_up);
+        Ok(())
+    }
+
+    /// The power-up marked by `glyph` on the board.
+    pub fn get(&self, glyph: char) -> Option<&dyn PowerUp> {
+        self.power_ups
+            .iter()
+            .find(|power_up| power_up.glyph() == glyph)
+            .map(|power_up| power_up.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn PowerUp> {
+        self.power_ups.iter().map(|power_up| power_up.as_ref())
+    }
+
+    pub fn glyphs(&self) -> Vec<char> {
+        self.iter().map(|power_up| power_up.glyph()).collect()
+    }
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Makes `registry` the power-ups in play. Only works before anything has
+/// looked at them; returns whether it did.
+pub fn install(registry: Registry) -> bool {
+    REGISTRY.set(registry).is_ok()
+}
+
+/// The power-ups in play: the installed ones, or the built-in ones if none
+/// were.
+pub fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::built_in)
+}
+
+/// Whether `cell` holds a power-up a piece can land on.
+pub fn is_power_up(cell: char) -> bool {
+    registry().get(cell).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::MAX_BOARD_SIZE;
+
+    /// Adds the definition in `fields` to the built-in power-ups, the way a
+    /// definition file is loaded.
+    fn define(fields: &str) -> Result<(), String> {
+        let contents = format!(
+            "(id: \"mine\", name: \"Mine\", color: (0.5, 0.5, 0.5), cue: Explosion, {})",
+            fields
+        );
+        let definition = parse_definition("mine.ron", &contents)?;
+        Registry::built_in().define(definition, None)
+    }
+
+    #[test]
+    fn valid_definition_is_added() {
+        assert_eq!(define("glyph: 'M', effect: [ClearCells([(0, 0)])]"), Ok(()));
+    }
+
+    #[test]
+    fn unknown_field_names_the_file_and_the_field() {
+        let err = define("glyph: 'M', effects: [SkipTurns(1)]").unwrap_err();
+
+        assert!(err.starts_with("mine.ron: "), "{}", err);
+        assert!(err.contains("effects"), "{}", err);
+    }
+
+    #[test]
+    fn glyph_already_taken_is_rejected() {
+        assert_eq!(
+            define("glyph: 'B', effect: [SkipTurns(1)]"),
+            Err("'mine' uses the same id or glyph as 'bomb'.".to_string())
+        );
+        assert_eq!(
+            define("glyph: '#', effect: [SkipTurns(1)]"),
+            Err("glyph '#' of 'mine' is already used by the board.".to_string())
+        );
+    }
+
+    #[test]
+    fn primitives_out_of_range_are_rejected() {
+        assert_eq!(
+            define("glyph: 'M', effect: [ClearCells([(0, 0), (-12, 0)])]"),
+            Err(format!(
+                "cell (-12, 0) is off any board; offsets must be below {}.",
+                MAX_BOARD_SIZE
+            ))
+        );
+        assert_eq!(
+            define("glyph: 'M', effect: [SkipTurns(0)]"),
+            Err("SkipTurns needs at least 1 turn.".to_string())
+        );
+        assert_eq!(
+            define("glyph: 'M', effect: [TargetPiece(0)]"),
+            Err(format!(
+                "TargetPiece needs a radius from 1 to {}.",
+                MAX_BOARD_SIZE - 1
+            ))
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use super::{Cue, Effect, PowerUp, SpawnRegion, Trigger, Visual};
+use crate::game::{Game, MAX_BOARD_SIZE};
+use serde::Deserialize;
+
+/// Cells relative to the one a power-up is set off in, as (rows up, columns
+/// right).
+pub type Pattern = Vec<(isize, isize)>;
+
+/// A building block of a power-up's effect.
+#[derive(Debug, Clone, Deserialize)]
+pub enum Primitive {
+    /// Empties the cells, whatever is in them. Clearing the power-up's own
+    /// cell destroys the piece that set it off.
+    ClearCells(Pattern),
+    /// Drops an obstacle down from each cell, or from on top of whatever
+    /// fills it.
+    PlaceObstacles(Pattern),
+    /// The player who set it off moves again this many times.
+    SkipTurns(u32),
+    /// Turns the opponent's pieces in the cells into the player's.
+    ConvertPieces(Pattern),
+    /// Lets the player pick one of the opponent's pieces within this many
+    /// cells to destroy.
+    TargetPiece(usize),
+}
+
+/// A power-up as written in a definition file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Definition {
+    id: String,
+    name: String,
+    glyph: char,
+    /// sRGB, used when the theme doesn't pick a color.
+    color: (f32, f32, f32),
+    /// Image in the assets folder, drawn by skins that use icons.
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(default = "default_spawn_weight")]
+    spawn_weight: u32,
+    #[serde(default)]
+    spawn_region: SpawnRegion,
+    cue: Cue,
+    /// Applied in order when the power-up is set off.
+    #[serde(default)]
+    effect: Vec<Primitive>,
+}
+
+fn default_spawn_weight() -> u32 {
+    1
+}
+
+impl Definition {
+    /// Checks the definition makes sense on its own; clashes with other
+    /// power-ups are caught by the registry. Only scripted power-ups can do
+    /// without an effect.
+    fn validate(&self, scripted: bool) -> Result<(), String> {
+        if self.id.is_empty() || self.id.contains(char::is_whitespace) {
+            return Err(format!(
+                "id '{}' must be a single word, e.g. \"bomb\".",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty.".to_string());
+        }
+        if self.glyph.is_whitespace() || self.glyph.is_control() {
+            return Err("glyph must be a visible character.".to_string());
+        }
+        let (red, green, blue) = self.color;
+        if ![red, green, blue]
+            .iter()
+            .all(|channel| (0.0..=1.0).contains(channel))
+        {
+            return Err("color channels must be between 0.0 and 1.0.".to_string());
+        }
+        if self
+            .icon
+            .as_ref()
+            .is_some_and(|icon| icon.trim().is_empty())
+        {
+            return Err("icon must be a path, or None.".to_string());
+        }
+        if let SpawnRegion::Rows(from, to) | SpawnRegion::Columns(from, to) = self.spawn_region {
+            if from > to {
+                return Err(format!(
+                    "spawn_region starts at {} but ends at {}.",
+                    from, to
+                ));
+            }
+        }
+        if self.effect.is_empty() && !scripted {
+            return Err("effect needs at least one primitive.".to_string());
+        }
+
+        let reach = MAX_BOARD_SIZE as isize;
+        for primitive in &self.effect {
+            match primitive {
+                Primitive::ClearCells(pattern)
+                | Primitive::PlaceObstacles(pattern)
+                | Primitive::ConvertPieces(pattern) => {
+                    if pattern.is_empty() {
+                        return Err(format!("{:?} has no cells.", primitive));
+                    }
+                    if let Some(offset) = pattern
+                        .iter()
+                        .find(|(rows, cols)| rows.abs() >= reach || cols.abs() >= reach)
+                    {
+                        return Err(format!(
+                            "cell {:?} is off any board; offsets must be below {}.",
+                            offset, reach
+                        ));
+                    }
+                }
+                Primitive::SkipTurns(0) => {
+                    return Err("SkipTurns needs at least 1 turn.".to_string());
+                }
+                Primitive::SkipTurns(_) => {}
+                Primitive::TargetPiece(radius) => {
+                    if !(1..MAX_BOARD_SIZE).contains(radius) {
+                        return Err(format!(
+                            "TargetPiece needs a radius from 1 to {}.",
+                            MAX_BOARD_SIZE - 1
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A power-up defined in a file, optionally with a script run after its
+/// effect.
+pub(super) struct DefinedPowerUp {
+    definition: Definition,
+    visual: Visual,
+    script: Option<Trigger>,
+}
+
+impl DefinedPowerUp {
+    pub(super) fn new(definition: Definition, script: Option<Trigger>) -> Result<Self, String> {
+        definition.validate(script.is_some())?;
+        let (red, green, blue) = definition.color;
+        let visual = Visual {
+            label: definition.glyph.to_string(),
+            color: [red, green, blue],
+            icon: definition.icon.clone(),
+            cue: definition.cue,
+        };
+        Ok(Self {
+            definition,
+            visual,
+            script,
+        })
+    }
+}
+
+/// The cell `offset` away from `row`, `col`, if it is on the board.
+fn target(game: &Game, row: usize, col: usize, offset: (isize, isize)) -> Option<(usize, usize)> {
+    let board = game.get_board();
+    let row = row.checked_add_signed(offset.0)?;
+    let col = col.checked_add_signed(offset.1)?;
+    (row < board.len() && col < board[0].len()).then_some((row, col))
+}
+
+impl PowerUp for DefinedPowerUp {
+    fn id(&self) -> &str {
+        &self.definition.id
+    }
+
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn glyph(&self) -> char {
+        self.definition.glyph
+    }
+
+    fn spawn_weight(&self) -> u32 {
+        self.definition.spawn_weight
+    }
+
+    fn spawn_region(&self) -> &SpawnRegion {
+        &self.definition.spawn_region
+    }
+
+    fn on_trigger(&self, game: &mut Game, row: usize, col: usize) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        for primitive in &self.definition.effect {
+            match primitive {
+                Primitive::ClearCells(pattern) => {
+                    for &offset in pattern {
+                        if let Some((row, col)) = target(game, row, col, offset) {
+                            game.clear_cell(row, col);
+                            effects.push(Effect::Cleared { row, col });
+                        }
+                    }
+                }
+                Primitive::PlaceObstacles(pattern) => {
+                    for &offset in pattern {
+                        let Some((row, col)) = target(game, row, col, offset) else {
+                            continue;
+                        };
+                        if let Some(row) = game.drop_obstacle(row, col) {
+                            effects.push(Effect::ObstaclePlaced { row, col });
+                        }
+                    }
+                }
+                Primitive::SkipTurns(turns) => {
+                    game.skip_turns(*turns);
+                    effects.push(Effect::SkipTurns(*turns));
+                }
+                Primitive::ConvertPieces(pattern) => {
+                    for &offset in pattern {
+                        let Some((row, col)) = target(game, row, col, offset) else {
+                            continue;
+                        };
+                        if let Some(player) = game.convert_piece(row, col) {
+                            effects.push(Effect::Converted { row, col, player });
+                        }
+                    }
+                }
+                // The piece goes once the player has picked it.
+                Primitive::TargetPiece(radius) => {
+                    game.start_targeting(row, col, self.definition.glyph, *radius);
+                }
+            }
+        }
+        if let Some(script) = &self.script {
+            effects.extend(script(game, row, col));
+        }
+        effects
+    }
+
+    fn visual(&self) -> &Visual {
+        &self.visual
+    }
+}